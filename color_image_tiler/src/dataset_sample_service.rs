@@ -4,6 +4,10 @@ use rand::Rng;
 
 use crate::{seed::Seed, TILE_COUNT, TILE_COUNT_SQRT};
 
+// the samples between neighbouring seeds decide where the tiles meet, they count more than the
+// random ones
+const DELIBERATE_SAMPLE_WEIGHT: f32 = 4.;
+
 pub struct DatasetSampleService {
     img: DynamicImage,
     scale: f32,
@@ -30,23 +34,21 @@ impl DatasetSampleService {
         })
     }
 
+    // the random samples come from `rng`, a seeded one makes the whole run reproducible
     pub fn get(
         &self,
         count: usize,
         params: &[f32; TILE_COUNT * 5],
-    ) -> Vec<DataPoint<{ TILE_COUNT * 5 }, 2, 3>> {
-        let mut rng = rand::thread_rng();
+        rng: &mut impl Rng,
+    ) -> Vec<DataPoint<2, 3>> {
         let random_samples: Vec<_> = (0..count)
             .map(|_| (rng.gen(), rng.gen()))
             .map(|(x, y)| (x, y, self.sample(x, y)))
             .filter(|(_, _, x)| x.is_some())
-            .map(|(x, y, c)| DataPoint {
-                input: [x, y],
-                output: c.unwrap(),
-            })
+            .map(|(x, y, c)| DataPoint::new([x, y], c.unwrap()))
             .collect();
 
-        let mut deliberate_samples: Vec<DataPoint<{ TILE_COUNT * 5 }, 2, 3>> = vec![];
+        let mut deliberate_samples: Vec<DataPoint<2, 3>> = vec![];
 
         let seeds = params.array_chunks::<5>().collect::<Vec<_>>();
 
@@ -86,10 +88,8 @@ impl DatasetSampleService {
                 let c = self.sample(mid_x, mid_y);
 
                 if let Some(c) = c {
-                    deliberate_samples.push(DataPoint {
-                        input: [mid_x, mid_y],
-                        output: c,
-                    });
+                    let sample = DataPoint::new([mid_x, mid_y], c);
+                    deliberate_samples.push(sample.with_weight(DELIBERATE_SAMPLE_WEIGHT));
                 }
             }
         }
@@ -125,9 +125,7 @@ fn main() {
     let (train_tx, train_rx) = channel();
     let (stats_tx, stats_rx) = channel();
 
-    let train_builder = thread::Builder::new()
-        .name("train_thread".into())
-        .stack_size(2 * 1024 * 1024 * 1024);
+    let train_builder = thread::Builder::new().name("train_thread".into());
 
     let stats_builder = thread::Builder::new().name("stats_thread".into());
 
@@ -1,12 +1,17 @@
 use std::{array, sync::mpsc::Sender};
 
-use ia_engine::trainer::{CriticalityCue, Trainer};
+use ia_engine::trainer::{initializer::Initializer, CriticalityCue, StepOutcome, Trainer};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 
 use crate::{
     dataset_sample_service::DatasetSampleService, tiler, TrainerComunicationCodes, TILE_COUNT,
     TILE_COUNT_SQRT,
 };
 
+// drives both the trainer and the pixel sampling, the same seed reproduces a run
+const SEED: u64 = 2;
+
 fn max_speed_param_translator<const P: usize>(params: &[f32; P], vector: &[f32; P]) -> [f32; P] {
     array::from_fn(|i| (params[i] + vector[i]).max(0.).min(1.))
 }
@@ -29,7 +34,11 @@ pub(crate) fn train_work(
         tiler,
         max_speed_param_translator,
         (),
-    );
+    )
+    // the params are positions and colors
+    .with_initializer(Initializer::Uniform { min: 0., max: 1. })
+    .with_seed(SEED);
+    let mut rng = ChaCha8Rng::seed_from_u64(SEED);
 
     if let Some(ref tx) = tx {
         tx.send(TrainerComunicationCodes::Msg((
@@ -47,7 +56,7 @@ pub(crate) fn train_work(
     let mut local_minimum_count = 0;
     let mut iterations = 0;
     while local_minimum_count < 100 {
-        let pixels = dataset_service.get(100, &trainer.get_model_params());
+        let pixels = dataset_service.get(100, &trainer.get_model_params(), &mut rng);
 
         iterations += 1;
 
@@ -61,7 +70,11 @@ pub(crate) fn train_work(
             return;
         }
 
-        if !trainer.train_step_asintotic_search::<true, _, _>(&pixels, &pixels, pixels.len(), pixels.len()) {
+        let report = trainer
+            .train_step::<true, false, _, _>(&pixels, &pixels)
+            .unwrap();
+
+        if report.outcome == StepOutcome::Stalled {
             local_minimum_count += 1;
             trainer.shake(0.1);
         } else {
@@ -0,0 +1,149 @@
+#![feature(test)]
+
+extern crate test;
+
+use std::array;
+use std::ops::{Index, IndexMut};
+
+use ia_engine::dual::extended_arithmetic::ExtendedArithmetic;
+use ia_engine::dual::Dual;
+use ia_engine::simd_arr::dense_simd::DenseSimd;
+use ia_engine::simd_arr::SimdArr;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use test::{black_box, Bencher};
+
+// The network of the perceptron example: same structure, weights of each layer followed by the
+// bias of each neuron and sigmoid activations.
+const STRUCTURE: [usize; 4] = [14 * 14, 30, 20, 10];
+const P: usize = (14 * 14 + 1) * 30 + (30 + 1) * 20 + (20 + 1) * 10;
+
+// DenseSimd as it was before std::simd (on the heap like the current one), the baseline of the
+// benchmarks. It lacks the fused accumulate_scaled, so each call clones rhs. On the default
+// x86_64 target the perceptron takes about 34ms against 46ms and accumulate_scaled about 110µs
+// against 260µs.
+#[derive(Clone, Debug)]
+struct ScalarLoops<const S: usize>(Box<[f32; S]>);
+
+impl<const S: usize> SimdArr<S> for ScalarLoops<S> {
+    type Scalar = f32;
+
+    fn new_from_array(data: [f32; S]) -> Self {
+        Self(Box::new(data))
+    }
+
+    fn new_from_value_and_pos(val: f32, pos: usize) -> Self {
+        let mut ret = Self::zero();
+        ret.0[pos] = val;
+        ret
+    }
+
+    fn zero() -> Self {
+        Self(Box::new([0.; S]))
+    }
+
+    fn neg(&mut self) {
+        for x in self.0.iter_mut() {
+            *x = -*x;
+        }
+    }
+
+    fn to_array(&self) -> [f32; S] {
+        *self.0
+    }
+
+    fn acumulate(&mut self, rhs: &Self) {
+        for i in 0..S {
+            self.0[i] += rhs.0[i];
+        }
+    }
+
+    fn multiply(&mut self, rhs: f32) {
+        for x in self.0.iter_mut() {
+            *x *= rhs;
+        }
+    }
+
+    fn is_finite(&self) -> bool {
+        self.0.iter().all(|x| x.is_finite())
+    }
+}
+
+impl<const S: usize> Index<usize> for ScalarLoops<S> {
+    type Output = f32;
+
+    fn index(&self, index: usize) -> &f32 {
+        &self.0[index]
+    }
+}
+
+impl<const S: usize> IndexMut<usize> for ScalarLoops<S> {
+    fn index_mut(&mut self, index: usize) -> &mut f32 {
+        &mut self.0[index]
+    }
+}
+
+fn perceptron<N: Clone + From<f32> + ExtendedArithmetic>(
+    params: &[N],
+    input: &[f32; 14 * 14],
+) -> Vec<N> {
+    let mut propagation: Vec<N> = input.iter().map(|x| N::from(*x)).collect();
+    let mut cursor = 0;
+
+    for layer in STRUCTURE.windows(2) {
+        propagation = (0..layer[1])
+            .map(|_| {
+                let mut neuron = params[cursor + layer[0]].clone();
+                for (i, x) in propagation.iter().enumerate() {
+                    neuron.accumulate_product(&params[cursor + i], x);
+                }
+                cursor += layer[0] + 1;
+                neuron.sigmoid()
+            })
+            .collect();
+    }
+
+    propagation
+}
+
+fn bench_perceptron<S: SimdArr<P, Scalar = f32>>(b: &mut Bencher) {
+    let mut rng = ChaCha8Rng::seed_from_u64(2);
+    let params: Vec<Dual<P, S>> = (0..P)
+        .map(|i| Dual::new_param(rng.gen::<f32>() - 0.5, i))
+        .collect();
+    let input = array::from_fn(|_| rng.gen());
+
+    b.iter(|| perceptron(black_box(&params), black_box(&input)));
+}
+
+#[bench]
+fn perceptron_dense_simd(b: &mut Bencher) {
+    bench_perceptron::<DenseSimd<P>>(b);
+}
+
+#[bench]
+fn perceptron_scalar_loops(b: &mut Bencher) {
+    bench_perceptron::<ScalarLoops<P>>(b);
+}
+
+fn bench_accumulate_scaled<S: SimdArr<P, Scalar = f32>>(b: &mut Bencher) {
+    let mut rng = ChaCha8Rng::seed_from_u64(2);
+    let mut lhs = S::new_from_array(array::from_fn(|_| rng.gen()));
+    let rhs = S::new_from_array(array::from_fn(|_| rng.gen()));
+
+    b.iter(|| {
+        for _ in 0..100 {
+            lhs.accumulate_scaled(black_box(&rhs), black_box(1e-3));
+        }
+    });
+}
+
+#[bench]
+fn accumulate_scaled_dense_simd(b: &mut Bencher) {
+    bench_accumulate_scaled::<DenseSimd<P>>(b);
+}
+
+#[bench]
+fn accumulate_scaled_scalar_loops(b: &mut Bencher) {
+    bench_accumulate_scaled::<ScalarLoops<P>>(b);
+}
@@ -0,0 +1,111 @@
+use std::{
+    fmt::Debug,
+    ops::{Add, Div, Mul, Sub},
+};
+
+use crate::{
+    dual::{extended_arithmetic::ExtendedArithmetic, Dual},
+    dyn_dual::DynDual,
+    reverse::Reverse,
+    scalar::Scalar,
+    simd_arr::SimdArr,
+};
+
+// The number type a Trainer differentiates its model with, Scalar is the type of its real part
+// and of the model's inputs and outputs
+pub trait Differentiable:
+    ExtendedArithmetic
+    + Clone
+    + Debug
+    + From<f32>
+    + PartialOrd<<Self as Differentiable>::Scalar>
+    + Add<Self, Output = Self>
+    + Add<<Self as Differentiable>::Scalar, Output = Self>
+    + Sub<Self, Output = Self>
+    + Sub<<Self as Differentiable>::Scalar, Output = Self>
+    + Mul<Self, Output = Self>
+    + Mul<<Self as Differentiable>::Scalar, Output = Self>
+    + Div<Self, Output = Self>
+    + Div<<Self as Differentiable>::Scalar, Output = Self>
+    + Send
+    + Sync
+{
+    type Scalar: Scalar;
+
+    fn new_param(real: Self::Scalar, i: usize) -> Self;
+
+    fn get_real(&self) -> Self::Scalar;
+
+    fn set_real(&mut self, val: Self::Scalar);
+
+    // writes d(self)/d(param i) into gradient[i], gradient is expected to be zeroed
+    fn write_gradient(&self, gradient: &mut [Self::Scalar]);
+}
+
+// the operators against scalars only exist for f32 and f64 duals
+impl<const P: usize, S: SimdArr<P>> Differentiable for Dual<P, S>
+where
+    Self: PartialOrd<S::Scalar>
+        + Add<S::Scalar, Output = Self>
+        + Sub<S::Scalar, Output = Self>
+        + Mul<S::Scalar, Output = Self>
+        + Div<S::Scalar, Output = Self>,
+{
+    type Scalar = S::Scalar;
+
+    fn new_param(real: S::Scalar, i: usize) -> Self {
+        Dual::new_param(real, i)
+    }
+
+    fn get_real(&self) -> S::Scalar {
+        Dual::get_real(self)
+    }
+
+    fn set_real(&mut self, val: S::Scalar) {
+        Dual::set_real(self, val)
+    }
+
+    fn write_gradient(&self, gradient: &mut [S::Scalar]) {
+        gradient.copy_from_slice(&Dual::get_gradient(self))
+    }
+}
+
+impl Differentiable for DynDual {
+    type Scalar = f32;
+
+    fn new_param(real: f32, i: usize) -> Self {
+        DynDual::new_param(real, i)
+    }
+
+    fn get_real(&self) -> f32 {
+        DynDual::get_real(self)
+    }
+
+    fn set_real(&mut self, val: f32) {
+        DynDual::set_real(self, val)
+    }
+
+    fn write_gradient(&self, gradient: &mut [f32]) {
+        DynDual::write_gradient(self, gradient)
+    }
+}
+
+impl Differentiable for Reverse {
+    type Scalar = f32;
+
+    fn new_param(real: f32, i: usize) -> Self {
+        Reverse::new_param(real, i)
+    }
+
+    fn get_real(&self) -> f32 {
+        Reverse::get_real(self)
+    }
+
+    fn set_real(&mut self, val: f32) {
+        Reverse::set_real(self, val)
+    }
+
+    fn write_gradient(&self, gradient: &mut [f32]) {
+        Reverse::write_gradient(self, gradient)
+    }
+}
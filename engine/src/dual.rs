@@ -5,60 +5,62 @@ pub mod multiply;
 pub mod substraction;
 mod tests;
 
+use crate::dual::extended_arithmetic::ExtendedArithmetic;
+use crate::scalar::Scalar;
 use crate::simd_arr::SimdArr;
 
+// The scalar (f32 or f64) is the one of the SimdArr, ie: Dual<P, DenseSimd<P, f64>>
 #[derive(Clone, Debug)]
 
 pub struct Dual<const P: usize, S: SimdArr<P>> {
-    real: f32,
+    real: S::Scalar,
     sigma: S,
 }
 
 impl<const P: usize, S: SimdArr<P>> From<f32> for Dual<P, S> {
     fn from(value: f32) -> Self {
-        Self::new(value)
+        Self::new(value.into())
     }
 }
 
 impl<const P: usize, S: SimdArr<P>> Dual<P, S> {
-    pub fn new_param(real: f32, i: usize) -> Dual<P, S> {
+    pub fn new_param(real: S::Scalar, i: usize) -> Dual<P, S> {
         Self {
-            real: real,
-            sigma: S::new_from_value_and_pos(1., i),
+            real,
+            sigma: S::new_from_value_and_pos(S::Scalar::ONE, i),
         }
     }
 
-    pub fn check_nan(&self) {
-        assert!(self.real.is_finite());
-        self.sigma.check_nan();
+    pub fn is_finite(&self) -> bool {
+        self.real.is_finite() && self.sigma.is_finite()
     }
 
-    pub(crate) fn set_real(&mut self, val: f32) {
+    pub(crate) fn set_real(&mut self, val: S::Scalar) {
         self.real = val
     }
 
     pub fn zero() -> Self {
         Self {
-            real: 0.,
+            real: S::Scalar::ZERO,
             sigma: S::zero(),
         }
     }
 
-    pub fn get_gradient(&self) -> [f32; P] {
+    pub fn get_gradient(&self) -> [S::Scalar; P] {
         self.sigma.to_array()
     }
 
-    pub fn get_real(&self) -> f32 {
+    pub fn get_real(&self) -> S::Scalar {
         self.real
     }
 
-    pub fn new(real: f32) -> Self {
+    pub fn new(real: S::Scalar) -> Self {
         let mut ret = Self::zero();
         ret.real = real;
         ret
     }
 
-    pub fn new_full(real: f32, sigma: [f32; P]) -> Self {
+    pub fn new_full(real: S::Scalar, sigma: [S::Scalar; P]) -> Self {
         Self {
             real,
             sigma: SimdArr::new_from_array(sigma),
@@ -80,26 +82,29 @@ impl<const P: usize, S: SimdArr<P>> PartialOrd for Dual<P, S> {
 
 impl<const P: usize, S: SimdArr<P>> Eq for Dual<P, S> {}
 
-impl<const P: usize, S: SimdArr<P>> PartialEq<f32> for Dual<P, S> {
-    fn eq(&self, other: &f32) -> bool {
-        self.real.eq(other)
-    }
-}
+// a single impl over S::Scalar would overlap with the ones against Dual, so there is one per
+// scalar type
+macro_rules! scalar_comparisons {
+    ($t:ty) => {
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> PartialEq<$t> for Dual<P, S> {
+            fn eq(&self, other: &$t) -> bool {
+                self.real.eq(other)
+            }
+        }
 
-impl<const P: usize, S: SimdArr<P>> PartialOrd<f32> for Dual<P, S> {
-    fn partial_cmp(&self, other: &f32) -> Option<std::cmp::Ordering> {
-        self.real.partial_cmp(other)
-    }
-}
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> PartialOrd<$t> for Dual<P, S> {
+            fn partial_cmp(&self, other: &$t) -> Option<std::cmp::Ordering> {
+                self.real.partial_cmp(other)
+            }
+        }
 
-impl<const P: usize, S: SimdArr<P>> From<Dual<P, S>> for f32 {
-    fn from(value: Dual<P, S>) -> Self {
-        value.get_real()
-    }
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> From<Dual<P, S>> for $t {
+            fn from(value: Dual<P, S>) -> Self {
+                value.get_real()
+            }
+        }
+    };
 }
 
-fn check_nan<const P: usize, S: SimdArr<P>>(d: Dual<P, S>) -> Dual<P, S> {
-    d.check_nan();
-
-    d
-}
+scalar_comparisons!(f32);
+scalar_comparisons!(f64);
@@ -2,7 +2,7 @@ use std::ops::Add;
 
 use crate::simd_arr::SimdArr;
 
-use super::{check_nan, Dual};
+use super::Dual;
 
 impl<const P: usize, S: SimdArr<P>> Add<Dual<P, S>> for Dual<P, S> {
     type Output = Dual<P, S>;
@@ -11,16 +11,24 @@ impl<const P: usize, S: SimdArr<P>> Add<Dual<P, S>> for Dual<P, S> {
         self.real += rhs.real;
         self.sigma.acumulate(&rhs.sigma);
 
-        check_nan(self)
+        self
     }
 }
 
-impl<const P: usize, S: SimdArr<P>> Add<f32> for Dual<P, S> {
-    type Output = Dual<P, S>;
+// one impl per scalar type, see scalar_comparisons
+macro_rules! add_scalar {
+    ($t:ty) => {
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> Add<$t> for Dual<P, S> {
+            type Output = Dual<P, S>;
 
-    fn add(mut self, rhs: f32) -> Self::Output {
-        self.real += rhs;
+            fn add(mut self, rhs: $t) -> Self::Output {
+                self.real += rhs;
 
-        check_nan(self)
-    }
+                self
+            }
+        }
+    };
 }
+
+add_scalar!(f32);
+add_scalar!(f64);
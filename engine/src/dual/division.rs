@@ -1,8 +1,9 @@
 use std::ops::Div;
 
+use crate::scalar::Scalar;
 use crate::simd_arr::SimdArr;
 
-use super::{check_nan, Dual};
+use super::Dual;
 
 impl<const P: usize, S: SimdArr<P>> Div<Dual<P, S>> for Dual<P, S> {
     type Output = Dual<P, S>;
@@ -14,22 +15,30 @@ impl<const P: usize, S: SimdArr<P>> Div<Dual<P, S>> for Dual<P, S> {
         rhs.sigma.neg();
         self.sigma.acumulate(&rhs.sigma);
 
-        self.sigma.multiply(1. / (rhs.real * rhs.real));
+        self.sigma.multiply(S::Scalar::ONE / (rhs.real * rhs.real));
 
         self.real /= rhs.real;
 
-        check_nan(self)
+        self
     }
 }
 
-impl<const P: usize, S: SimdArr<P>> Div<f32> for Dual<P, S> {
-    type Output = Dual<P, S>;
+// one impl per scalar type, see scalar_comparisons
+macro_rules! div_scalar {
+    ($t:ty) => {
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> Div<$t> for Dual<P, S> {
+            type Output = Dual<P, S>;
 
-    fn div(mut self, rhs: f32) -> Self::Output {
-        self.real /= rhs;
+            fn div(mut self, rhs: $t) -> Self::Output {
+                self.real /= rhs;
 
-        self.sigma.multiply(1. / rhs);
+                self.sigma.multiply(1. / rhs);
 
-        check_nan(self)
-    }
+                self
+            }
+        }
+    };
 }
+
+div_scalar!(f32);
+div_scalar!(f64);
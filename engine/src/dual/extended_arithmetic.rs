@@ -1,6 +1,9 @@
+pub(crate) mod derivatives;
+
+use crate::scalar::Scalar;
 use crate::simd_arr::SimdArr;
 
-use super::{check_nan, Dual};
+use super::Dual;
 
 pub trait ExtendedArithmetic {
     fn sqrt(self) -> Self;
@@ -17,6 +20,33 @@ pub trait ExtendedArithmetic {
 
     fn relu(self) -> Self;
 
+    fn ln(self) -> Self;
+
+    fn powf(self, n: f32) -> Self;
+
+    fn powi(self, n: i32) -> Self;
+
+    fn tanh(self) -> Self;
+
+    fn sin(self) -> Self;
+
+    fn cos(self) -> Self;
+
+    fn softplus(self) -> Self;
+
+    fn leaky_relu(self, slope: f32) -> Self;
+
+    // tanh approximation
+    fn gelu(self) -> Self;
+
+    // on ties self is returned
+    fn min(self, other: Self) -> Self;
+
+    fn max(self, other: Self) -> Self;
+
+    // self is y, other is x
+    fn atan2(self, other: Self) -> Self;
+
     fn sqrt_on_mut(&mut self);
     fn exp_on_mut(&mut self);
     fn neg_on_mut(&mut self);
@@ -24,8 +54,30 @@ pub trait ExtendedArithmetic {
     fn abs_on_mut(&mut self);
     fn sigmoid_on_mut(&mut self);
     fn relu_on_mut(&mut self);
+    fn ln_on_mut(&mut self);
+    fn powf_on_mut(&mut self, n: f32);
+    fn powi_on_mut(&mut self, n: i32);
+    fn tanh_on_mut(&mut self);
+    fn sin_on_mut(&mut self);
+    fn cos_on_mut(&mut self);
+    fn softplus_on_mut(&mut self);
+    fn leaky_relu_on_mut(&mut self, slope: f32);
+    fn gelu_on_mut(&mut self);
 
     fn accumulate(&mut self, x: &Self);
+
+    // self += a * b, implementations skip the temporary product where they can
+    fn accumulate_product(&mut self, a: &Self, b: &Self);
+
+    fn is_finite(&self) -> bool;
+}
+
+impl<const P: usize, S: SimdArr<P>> Dual<P, S> {
+    // applies a function given its value and derivative at real
+    fn chain(&mut self, value: S::Scalar, derivative: S::Scalar) {
+        self.real = value;
+        self.sigma.multiply(derivative);
+    }
 }
 
 impl<const P: usize, S: SimdArr<P>> ExtendedArithmetic for Dual<P, S> {
@@ -64,10 +116,80 @@ impl<const P: usize, S: SimdArr<P>> ExtendedArithmetic for Dual<P, S> {
         self
     }
 
+    fn ln(mut self) -> Self {
+        self.ln_on_mut();
+        self
+    }
+
+    fn powf(mut self, n: f32) -> Self {
+        self.powf_on_mut(n);
+        self
+    }
+
+    fn powi(mut self, n: i32) -> Self {
+        self.powi_on_mut(n);
+        self
+    }
+
+    fn tanh(mut self) -> Self {
+        self.tanh_on_mut();
+        self
+    }
+
+    fn sin(mut self) -> Self {
+        self.sin_on_mut();
+        self
+    }
+
+    fn cos(mut self) -> Self {
+        self.cos_on_mut();
+        self
+    }
+
+    fn softplus(mut self) -> Self {
+        self.softplus_on_mut();
+        self
+    }
+
+    fn leaky_relu(mut self, slope: f32) -> Self {
+        self.leaky_relu_on_mut(slope);
+        self
+    }
+
+    fn gelu(mut self) -> Self {
+        self.gelu_on_mut();
+        self
+    }
+
+    fn min(self, other: Self) -> Self {
+        if other.real < self.real {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other.real > self.real {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn atan2(mut self, other: Self) -> Self {
+        let [value, dy, dx, ..] = derivatives::atan2(self.real, other.real);
+        let mut other_sigma = other.sigma;
+        other_sigma.multiply(dx);
+
+        self.chain(value, dy);
+        self.sigma.acumulate(&other_sigma);
+        self
+    }
+
     fn sqrt_on_mut(&mut self) {
-        self.real = self.real.sqrt();
-        self.sigma.multiply(1. / (2. * self.real.sqrt()));
-        self.check_nan();
+        let root = self.real.sqrt();
+        self.chain(root, S::Scalar::ONE / (root + root));
     }
 
     fn exp_on_mut(&mut self) {
@@ -77,105 +199,262 @@ impl<const P: usize, S: SimdArr<P>> ExtendedArithmetic for Dual<P, S> {
 
     fn neg_on_mut(&mut self) {
         self.real = -self.real;
-        self.sigma.multiply(-1.);
-        self.check_nan();
+        self.sigma.neg();
     }
 
     fn pow2_on_mut(&mut self) {
-        self.real *= self.real;
-        self.sigma.multiply(self.real * 2.);
-        self.check_nan();
+        self.chain(self.real * self.real, self.real + self.real);
     }
 
     fn abs_on_mut(&mut self) {
-        if self.real < 0. {
+        if self.real < S::Scalar::ZERO {
             self.real = -self.real;
             self.sigma.neg();
-            self.check_nan();
         }
     }
 
     fn sigmoid_on_mut(&mut self) {
         self.real = self.real.sigmoid();
 
-        self.sigma.multiply(self.real * (1. - self.real));
-        self.check_nan();
+        self.sigma
+            .multiply(self.real * (S::Scalar::ONE - self.real));
     }
 
     fn relu_on_mut(&mut self) {
-        if self.real < 0. {
-            self.real = 0.;
+        if self.real < S::Scalar::ZERO {
+            self.real = S::Scalar::ZERO;
             self.sigma = S::zero();
-            self.check_nan();
         }
     }
 
-    fn accumulate(&mut self, x: &Dual<P, S>) {
-        self.real += x.real;
-        self.sigma.acumulate(&x.sigma);
-        self.check_nan();
+    fn ln_on_mut(&mut self) {
+        self.sigma.multiply(S::Scalar::ONE / self.real);
+        self.real = self.real.ln();
     }
-}
 
-impl ExtendedArithmetic for f32 {
-    fn sqrt(self) -> Self {
-        self.sqrt()
+    fn powf_on_mut(&mut self, n: f32) {
+        let [value, derivative, _] = derivatives::powf(self.real, n);
+        self.chain(value, derivative);
     }
 
-    fn neg(self) -> Self {
-        -self
+    fn powi_on_mut(&mut self, n: i32) {
+        let [value, derivative, _] = derivatives::powi(self.real, n);
+        self.chain(value, derivative);
     }
 
-    fn exp(self) -> Self {
-        self.exp()
+    fn tanh_on_mut(&mut self) {
+        let [value, derivative, _] = derivatives::tanh(self.real);
+        self.chain(value, derivative);
     }
 
-    fn pow2(self) -> Self {
-        self * self
+    fn sin_on_mut(&mut self) {
+        let [value, derivative, _] = derivatives::sin(self.real);
+        self.chain(value, derivative);
     }
 
-    fn abs(self) -> Self {
-        self.abs()
+    fn cos_on_mut(&mut self) {
+        let [value, derivative, _] = derivatives::cos(self.real);
+        self.chain(value, derivative);
     }
 
-    fn relu(self) -> Self {
-        self.max(0.)
+    fn softplus_on_mut(&mut self) {
+        let [value, derivative, _] = derivatives::softplus(self.real);
+        self.chain(value, derivative);
     }
 
-    fn sigmoid(mut self) -> Self {
-        self.sigmoid_on_mut();
-        self
+    fn leaky_relu_on_mut(&mut self, slope: f32) {
+        let [value, derivative, _] = derivatives::leaky_relu(self.real, slope);
+        self.chain(value, derivative);
     }
 
-    fn sqrt_on_mut(&mut self) {
-        *self = self.sqrt()
-    }
-
-    fn neg_on_mut(&mut self) {
-        *self = -*self;
-    }
-
-    fn exp_on_mut(&mut self) {
-        *self = self.exp()
-    }
-
-    fn pow2_on_mut(&mut self) {
-        *self = *self * *self;
+    fn gelu_on_mut(&mut self) {
+        let [value, derivative, _] = derivatives::gelu(self.real);
+        self.chain(value, derivative);
     }
 
-    fn abs_on_mut(&mut self) {
-        *self = self.abs();
+    fn accumulate(&mut self, x: &Dual<P, S>) {
+        self.real += x.real;
+        self.sigma.acumulate(&x.sigma);
     }
 
-    fn sigmoid_on_mut(&mut self) {
-        *self = 1. / (1. + (-*self).exp());
+    fn accumulate_product(&mut self, a: &Dual<P, S>, b: &Dual<P, S>) {
+        self.real += a.real * b.real;
+        self.sigma.accumulate_scaled(&a.sigma, b.real);
+        self.sigma.accumulate_scaled(&b.sigma, a.real);
     }
 
-    fn relu_on_mut(&mut self) {
-        *self = self.max(0.);
+    fn is_finite(&self) -> bool {
+        Dual::is_finite(self)
     }
+}
 
-    fn accumulate(&mut self, x: &f32) {
-        *self += x;
-    }
+// f32 and f64 share every line
+macro_rules! float_extended_arithmetic {
+    ($t:ty) => {
+        impl ExtendedArithmetic for $t {
+            fn sqrt(self) -> Self {
+                self.sqrt()
+            }
+
+            fn neg(self) -> Self {
+                -self
+            }
+
+            fn exp(self) -> Self {
+                self.exp()
+            }
+
+            fn pow2(self) -> Self {
+                self * self
+            }
+
+            fn abs(self) -> Self {
+                self.abs()
+            }
+
+            fn relu(self) -> Self {
+                self.max(0.)
+            }
+
+            fn sigmoid(mut self) -> Self {
+                self.sigmoid_on_mut();
+                self
+            }
+
+            fn ln(self) -> Self {
+                self.ln()
+            }
+
+            fn powf(self, n: f32) -> Self {
+                <$t>::powf(self, n.into())
+            }
+
+            fn powi(self, n: i32) -> Self {
+                self.powi(n)
+            }
+
+            fn tanh(self) -> Self {
+                self.tanh()
+            }
+
+            fn sin(self) -> Self {
+                self.sin()
+            }
+
+            fn cos(self) -> Self {
+                self.cos()
+            }
+
+            fn softplus(self) -> Self {
+                derivatives::softplus(self)[0]
+            }
+
+            fn leaky_relu(self, slope: f32) -> Self {
+                derivatives::leaky_relu(self, slope)[0]
+            }
+
+            fn gelu(self) -> Self {
+                derivatives::gelu(self)[0]
+            }
+
+            fn min(self, other: Self) -> Self {
+                if other < self {
+                    other
+                } else {
+                    self
+                }
+            }
+
+            fn max(self, other: Self) -> Self {
+                if other > self {
+                    other
+                } else {
+                    self
+                }
+            }
+
+            fn atan2(self, other: Self) -> Self {
+                self.atan2(other)
+            }
+
+            fn sqrt_on_mut(&mut self) {
+                *self = self.sqrt()
+            }
+
+            fn neg_on_mut(&mut self) {
+                *self = -*self;
+            }
+
+            fn exp_on_mut(&mut self) {
+                *self = self.exp()
+            }
+
+            fn pow2_on_mut(&mut self) {
+                *self = *self * *self;
+            }
+
+            fn abs_on_mut(&mut self) {
+                *self = self.abs();
+            }
+
+            fn sigmoid_on_mut(&mut self) {
+                *self = 1. / (1. + (-*self).exp());
+            }
+
+            fn relu_on_mut(&mut self) {
+                *self = self.max(0.);
+            }
+
+            fn ln_on_mut(&mut self) {
+                *self = self.ln();
+            }
+
+            fn powf_on_mut(&mut self, n: f32) {
+                *self = <$t>::powf(*self, n.into());
+            }
+
+            fn powi_on_mut(&mut self, n: i32) {
+                *self = self.powi(n);
+            }
+
+            fn tanh_on_mut(&mut self) {
+                *self = self.tanh();
+            }
+
+            fn sin_on_mut(&mut self) {
+                *self = self.sin();
+            }
+
+            fn cos_on_mut(&mut self) {
+                *self = self.cos();
+            }
+
+            fn softplus_on_mut(&mut self) {
+                *self = derivatives::softplus(*self)[0];
+            }
+
+            fn leaky_relu_on_mut(&mut self, slope: f32) {
+                *self = derivatives::leaky_relu(*self, slope)[0];
+            }
+
+            fn gelu_on_mut(&mut self) {
+                *self = derivatives::gelu(*self)[0];
+            }
+
+            fn accumulate(&mut self, x: &$t) {
+                *self += x;
+            }
+
+            fn accumulate_product(&mut self, a: &$t, b: &$t) {
+                *self += a * b;
+            }
+
+            fn is_finite(&self) -> bool {
+                <$t>::is_finite(*self)
+            }
+        }
+    };
 }
+
+float_extended_arithmetic!(f32);
+float_extended_arithmetic!(f64);
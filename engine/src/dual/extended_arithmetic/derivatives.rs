@@ -0,0 +1,159 @@
+use crate::scalar::Scalar;
+
+// [value, first derivative, second derivative] of the functions every number type implements,
+// so the rules are written once
+
+// coefficient * power, 0 whenever the coefficient is even if the power is infinite. The
+// derivatives of x^0 and x^1 would be 0 * inf = NaN at x = 0 otherwise
+fn scaled<T: Scalar>(coefficient: T, power: T) -> T {
+    if coefficient == T::ZERO {
+        T::ZERO
+    } else {
+        coefficient * power
+    }
+}
+
+pub(crate) fn powf<T: Scalar>(x: T, n: f32) -> [T; 3] {
+    let n_t = T::from(n);
+    [
+        x.powf(n),
+        scaled(n_t, x.powf(n - 1.)),
+        scaled(n_t * (n_t - T::ONE), x.powf(n - 2.)),
+    ]
+}
+
+pub(crate) fn powi<T: Scalar>(x: T, n: i32) -> [T; 3] {
+    let n_t = T::from(n as f32);
+    [
+        x.powi(n),
+        scaled(n_t, x.powi(n - 1)),
+        scaled(n_t * (n_t - T::ONE), x.powi(n - 2)),
+    ]
+}
+
+pub(crate) fn tanh<T: Scalar>(x: T) -> [T; 3] {
+    let t = x.tanh();
+    let derivative = T::ONE - t * t;
+    [t, derivative, T::from(-2.) * t * derivative]
+}
+
+pub(crate) fn sin<T: Scalar>(x: T) -> [T; 3] {
+    let (sin, cos) = (x.sin(), x.cos());
+    [sin, cos, -sin]
+}
+
+pub(crate) fn cos<T: Scalar>(x: T) -> [T; 3] {
+    let (sin, cos) = (x.sin(), x.cos());
+    [cos, -sin, -cos]
+}
+
+// ln(1 + e^x) written so it doesn't overflow for big x
+pub(crate) fn softplus<T: Scalar>(x: T) -> [T; 3] {
+    let sigmoid = x.sigmoid();
+    [
+        x.max(T::ZERO) + (-x.abs()).exp().ln_1p(),
+        sigmoid,
+        sigmoid * (T::ONE - sigmoid),
+    ]
+}
+
+pub(crate) fn leaky_relu<T: Scalar>(x: T, slope: f32) -> [T; 3] {
+    let slope = T::from(slope);
+    if x < T::ZERO {
+        [x * slope, slope, T::ZERO]
+    } else {
+        [x, T::ONE, T::ZERO]
+    }
+}
+
+// tanh approximation: 0.5x(1 + tanh(sqrt(2/pi)(x + 0.044715x^3)))
+pub(crate) fn gelu<T: Scalar>(x: T) -> [T; 3] {
+    let k = T::from(0.797_884_6);
+    let c = T::from(0.044715);
+    let half = T::from(0.5);
+
+    let u = k * (x + c * x * x * x);
+    let du = k * (T::ONE + T::from(3.) * c * x * x);
+    let ddu = k * T::from(6.) * c * x;
+    let t = u.tanh();
+    let sech2 = T::ONE - t * t;
+
+    [
+        half * x * (T::ONE + t),
+        half * (T::ONE + t) + half * x * sech2 * du,
+        sech2 * du + half * x * sech2 * (ddu - T::from(2.) * t * du * du),
+    ]
+}
+
+// [value, d/dy, d/dx, d2/dy2, d2/dydx, d2/dx2] of atan2(y, x)
+pub(crate) fn atan2<T: Scalar>(y: T, x: T) -> [T; 6] {
+    let r2 = x * x + y * y;
+    let r4 = r2 * r2;
+    let two = T::from(2.);
+    [
+        y.atan2(x),
+        x / r2,
+        -y / r2,
+        -two * x * y / r4,
+        (y * y - x * x) / r4,
+        two * x * y / r4,
+    ]
+}
+
+#[cfg(test)]
+mod derivatives_tests {
+    use super::*;
+
+    fn assert_derivatives<F: Fn(f32) -> [f32; 3]>(f: F, x: f32) {
+        let h = 1e-2;
+        let [_, derivative, second_derivative] = f(x);
+        let [forward, forward_derivative, _] = f(x + h);
+        let [backward, backward_derivative, _] = f(x - h);
+
+        let expected = (forward - backward) / (2. * h);
+        let expected_second = (forward_derivative - backward_derivative) / (2. * h);
+
+        assert!(
+            (derivative - expected).abs() < 1e-2 * (1. + expected.abs()),
+            "{x}: {derivative} vs {expected}"
+        );
+        assert!(
+            (second_derivative - expected_second).abs() < 1e-2 * (1. + expected_second.abs()),
+            "{x}: {second_derivative} vs {expected_second}"
+        );
+    }
+
+    #[test]
+    fn matches_finite_differences() {
+        for x in [-2.5, -1.2, -0.3, 0.4, 0.9, 1.7, 3.1] {
+            assert_derivatives(tanh, x);
+            assert_derivatives(sin, x);
+            assert_derivatives(cos, x);
+            assert_derivatives(softplus, x);
+            assert_derivatives(|x| leaky_relu(x, 0.1), x);
+            assert_derivatives(gelu, x);
+            assert_derivatives(|x| powi(x, 3), x);
+            assert_derivatives(|x| powi(x, -2), x);
+            assert_derivatives(|x| powf(x.abs(), 1.5), x.abs());
+        }
+
+        for n in 0..4 {
+            assert_derivatives(|x| powi(x, n), 0.);
+            assert_derivatives(|x| powf(x, n as f32), 0.);
+        }
+        assert_eq!(powi(0f32, 0), [1., 0., 0.]);
+        assert_eq!(powi(0f32, 1), [0., 1., 0.]);
+        assert_eq!(powf(0f32, 1.), [0., 1., 0.]);
+        assert_eq!(powf(0f32, 2.), [0., 0., 2.]);
+
+        let h = 1e-2;
+        let d = |y: f32, x: f32| atan2(y, x);
+        let [_, dy, dx, dyy, dyx, dxx] = d(0.7, -1.3);
+
+        assert!((dy - (d(0.7 + h, -1.3)[0] - d(0.7 - h, -1.3)[0]) / (2. * h)).abs() < 1e-3);
+        assert!((dx - (d(0.7, -1.3 + h)[0] - d(0.7, -1.3 - h)[0]) / (2. * h)).abs() < 1e-3);
+        assert!((dyy - (d(0.7 + h, -1.3)[1] - d(0.7 - h, -1.3)[1]) / (2. * h)).abs() < 1e-3);
+        assert!((dyx - (d(0.7, -1.3 + h)[1] - d(0.7, -1.3 - h)[1]) / (2. * h)).abs() < 1e-3);
+        assert!((dxx - (d(0.7, -1.3 + h)[2] - d(0.7, -1.3 - h)[2]) / (2. * h)).abs() < 1e-3);
+    }
+}
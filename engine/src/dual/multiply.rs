@@ -1,32 +1,46 @@
 use std::ops::Mul;
 
+use crate::dual::extended_arithmetic::ExtendedArithmetic;
 use crate::simd_arr::SimdArr;
 
-use super::{check_nan, Dual};
+use super::Dual;
 
 impl<const P: usize, S: SimdArr<P>> Mul<Dual<P, S>> for Dual<P, S> {
     type Output = Dual<P, S>;
 
-    fn mul(mut self, mut rhs: Dual<P, S>) -> Self::Output {
-        self.sigma.multiply(rhs.real);
-        rhs.sigma.multiply(self.real);
+    fn mul(mut self, rhs: Dual<P, S>) -> Self::Output {
+        self.sigma.axpy(rhs.real, &rhs.sigma, self.real);
 
         self.real *= rhs.real;
 
-        self.sigma.acumulate(&rhs.sigma);
+        self
+    }
+}
 
-        check_nan(self)
+impl<const P: usize, S: SimdArr<P>> Dual<P, S> {
+    // self * a + b, the product is never built
+    pub fn mul_add(self, a: &Dual<P, S>, mut b: Dual<P, S>) -> Dual<P, S> {
+        b.accumulate_product(&self, a);
+        b
     }
 }
 
-impl<const P: usize, S: SimdArr<P>> Mul<f32> for Dual<P, S> {
-    type Output = Dual<P, S>;
+// one impl per scalar type, see scalar_comparisons
+macro_rules! mul_scalar {
+    ($t:ty) => {
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> Mul<$t> for Dual<P, S> {
+            type Output = Dual<P, S>;
 
-    fn mul(mut self, rhs: f32) -> Self::Output {
-        self.real *= rhs;
+            fn mul(mut self, rhs: $t) -> Self::Output {
+                self.real *= rhs;
 
-        self.sigma.multiply(rhs);
+                self.sigma.multiply(rhs);
 
-        check_nan(self)
-    }
+                self
+            }
+        }
+    };
 }
+
+mul_scalar!(f32);
+mul_scalar!(f64);
@@ -2,26 +2,34 @@ use std::ops::Sub;
 
 use crate::simd_arr::SimdArr;
 
-use super::{check_nan, Dual};
+use super::Dual;
 
 impl<const P: usize, S: SimdArr<P>> Sub<Dual<P, S>> for Dual<P, S> {
     type Output = Dual<P, S>;
 
     fn sub(mut self, mut rhs: Dual<P, S>) -> Self::Output {
-        self.real -= &rhs.real;
+        self.real -= rhs.real;
         rhs.sigma.neg();
         self.sigma.acumulate(&rhs.sigma);
 
-        check_nan(self)
+        self
     }
 }
 
-impl<const P: usize, S: SimdArr<P>> Sub<f32> for Dual<P, S> {
-    type Output = Dual<P, S>;
+// one impl per scalar type, see scalar_comparisons
+macro_rules! sub_scalar {
+    ($t:ty) => {
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> Sub<$t> for Dual<P, S> {
+            type Output = Dual<P, S>;
 
-    fn sub(mut self, rhs: f32) -> Self::Output {
-        self.real -= rhs;
+            fn sub(mut self, rhs: $t) -> Self::Output {
+                self.real -= rhs;
 
-        check_nan(self)
-    }
+                self
+            }
+        }
+    };
 }
+
+sub_scalar!(f32);
+sub_scalar!(f64);
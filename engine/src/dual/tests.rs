@@ -5,7 +5,9 @@ mod dual_tests {
 
     use crate::{
         dual::{extended_arithmetic::ExtendedArithmetic, Dual},
-        simd_arr::hybrid_simd::HybridSimd,
+        gradient_check::gradient_check,
+        reverse::Reverse,
+        simd_arr::{dense_simd::DenseSimd, hybrid_simd::HybridSimd, SimdArr},
     };
 
     #[test]
@@ -35,4 +37,157 @@ mod dual_tests {
             assert_eq!(scalar_a * scalar_b, (a * b).get_real());
         }
     }
+
+    #[test]
+    fn extended_arithmetic_matches_reverse() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+        for _ in 0..1000 {
+            let a = rng.gen::<f32>() + 0.5;
+            let b = rng.gen::<f32>() + 0.5;
+
+            macro_rules! expression {
+                ($a:expr, $b:expr) => {{
+                    let (a, b) = ($a, $b);
+                    (a.clone() * b.clone()).powf(1.5) + (a.clone() / b.clone()).powi(-3)
+                        - (a.clone() - b.clone()).tanh() * (a.clone() * b.clone()).sin()
+                        + (a.clone() * b.clone()).cos() * (a.clone() - b.clone()).softplus()
+                        + (a.clone() - b.clone()).leaky_relu(0.1) * (a.clone() - b.clone()).gelu()
+                        + ExtendedArithmetic::min(a.clone(), b.clone())
+                            * ExtendedArithmetic::max(a.clone(), b.clone())
+                        + a.atan2(b - 1.)
+                }};
+            }
+
+            let dual: Dual<2, HybridSimd<2, 2>> =
+                expression!(Dual::new_param(a, 0), Dual::new_param(b, 1));
+            let reverse = expression!(Reverse::new_param(a, 0), Reverse::new_param(b, 1));
+
+            assert_eq!(dual.get_real(), reverse.get_real());
+
+            for (d, r) in dual
+                .get_gradient()
+                .into_iter()
+                .zip(reverse.get_gradient::<2>())
+            {
+                assert!((d - r).abs() < 1e-4 * (1. + r.abs()), "{d} vs {r}");
+            }
+        }
+    }
+
+    // every operator and ExtendedArithmetic method against finite differences of its f32 version,
+    // the same bodies run on f32 so they clone
+    #[allow(clippy::clone_on_copy)]
+    fn gradient_stress<S: SimdArr<2, Scalar = f32>>() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let points: Vec<(f32, f32)> = (0..200)
+            .map(|_| (rng.gen::<f32>() * 3. + 0.5, rng.gen::<f32>() * 3. + 0.5))
+            .chain([(0., 0.5), (0., 1.7)])
+            .collect();
+
+        for (a, b) in points {
+            macro_rules! assert_gradient {
+                (|$a:ident, $b:ident| $body:expr) => {
+                    let check = gradient_check::<2, 0, 1, (), S, _, _>(
+                        |params, _, _| {
+                            let ($a, $b) = (params[0].clone(), params[1].clone());
+                            [$body]
+                        },
+                        |params, _, _| {
+                            let ($a, $b) = (params[0], params[1]);
+                            [$body]
+                        },
+                        &[a, b],
+                        &[],
+                        &(),
+                        1e-3,
+                    );
+                    assert!(
+                        check.passes(1e-2),
+                        "{} at ({a}, {b}): {check:?}",
+                        stringify!($body)
+                    );
+                };
+            }
+
+            // the powers of 0 lose a factor of inf in their derivatives
+            for n in 0..4 {
+                assert_gradient!(|a, b| a.powi(n) * b);
+                assert_gradient!(|a, b| a.powf(n as f32) * b);
+            }
+            if a == 0. {
+                continue;
+            }
+
+            assert_gradient!(|a, b| a + b);
+            assert_gradient!(|a, b| a * 3. + b * 2.);
+            assert_gradient!(|a, b| a - b);
+            assert_gradient!(|a, b| (a - 2.) * b);
+            assert_gradient!(|a, b| a * b);
+            assert_gradient!(|a, b| a.clone() * a * b);
+            assert_gradient!(|a, b| a / b);
+            assert_gradient!(|a, b| (a / 3.) * b);
+            assert_gradient!(|a, b| (a * b).sqrt());
+            assert_gradient!(|a, b| (a * b).neg());
+            assert_gradient!(|a, b| (a - b).exp());
+            assert_gradient!(|a, b| (a * b).pow2());
+            assert_gradient!(|a, b| (a - b).sigmoid());
+            assert_gradient!(|a, b| (a * b).ln());
+            assert_gradient!(|a, b| (a * b).powf(1.5));
+            assert_gradient!(|a, b| (a + b).powi(-3));
+            assert_gradient!(|a, b| (a - b).tanh());
+            assert_gradient!(|a, b| (a * b).sin());
+            assert_gradient!(|a, b| (a * b).cos());
+            assert_gradient!(|a, b| (a - b).softplus());
+            assert_gradient!(|a, b| (a - b).gelu());
+            assert_gradient!(|a, b| a.atan2(b - 1.));
+            assert_gradient!(|a, b| {
+                let mut a = a;
+                a.accumulate(&b);
+                a
+            });
+            assert_gradient!(|a, b| {
+                let mut ab = a.clone() - b.clone();
+                ab.accumulate_product(&a, &b);
+                ab
+            });
+
+            // keep the finite differences away from the kinks
+            if (a - b).abs() > 0.1 {
+                assert_gradient!(|a, b| (a - b.clone()).abs() * b);
+                assert_gradient!(|a, b| (a.clone() - b).relu() * a);
+                assert_gradient!(|a, b| (a.clone() - b).leaky_relu(0.1) * a);
+                assert_gradient!(|a, b| ExtendedArithmetic::min(a.clone(), b) * a);
+                assert_gradient!(|a, b| ExtendedArithmetic::max(a.clone(), b) * a);
+            }
+        }
+    }
+
+    #[test]
+    fn mul_add_matches_unfused() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+        for _ in 0..1000 {
+            let [a, b, c]: [Dual<3, DenseSimd<3>>; 3] =
+                std::array::from_fn(|i| Dual::new_param(rng.gen_range(-10. ..10.), i));
+
+            let fused = a.clone().mul_add(&b, c.clone());
+            let unfused = a * b + c;
+
+            assert!((fused.get_real() - unfused.get_real()).abs() < 1e-4);
+            for (fused, unfused) in fused.get_gradient().into_iter().zip(unfused.get_gradient()) {
+                assert!((fused - unfused).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn dense_gradient_stress() {
+        gradient_stress::<DenseSimd<2>>();
+    }
+
+    #[test]
+    fn hybrid_gradient_stress() {
+        gradient_stress::<HybridSimd<2, 1>>();
+    }
 }
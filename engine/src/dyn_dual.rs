@@ -0,0 +1,103 @@
+pub mod addition;
+pub mod division;
+pub mod extended_arithmetic;
+pub mod multiply;
+pub mod substraction;
+mod tests;
+
+use crate::simd_arr::vec_simd::VecSimd;
+
+// Forward mode dual number whose parameter count is only known at runtime
+#[derive(Clone, Debug)]
+
+pub struct DynDual {
+    real: f32,
+    sigma: VecSimd,
+}
+
+impl From<f32> for DynDual {
+    fn from(value: f32) -> Self {
+        Self::new(value)
+    }
+}
+
+impl DynDual {
+    pub fn new_param(real: f32, i: usize) -> DynDual {
+        Self {
+            real,
+            sigma: VecSimd::new_from_value_and_pos(1., i),
+        }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.real.is_finite() && self.sigma.is_finite()
+    }
+
+    pub(crate) fn set_real(&mut self, val: f32) {
+        self.real = val
+    }
+
+    pub fn zero() -> Self {
+        Self {
+            real: 0.,
+            sigma: VecSimd::zero(),
+        }
+    }
+
+    pub fn write_gradient(&self, gradient: &mut [f32]) {
+        self.sigma.write_to(gradient)
+    }
+
+    pub fn get_gradient(&self, param_count: usize) -> Vec<f32> {
+        self.sigma.to_vec(param_count)
+    }
+
+    pub fn get_real(&self) -> f32 {
+        self.real
+    }
+
+    pub fn new(real: f32) -> Self {
+        let mut ret = Self::zero();
+        ret.real = real;
+        ret
+    }
+
+    pub fn new_full(real: f32, sigma: &[f32]) -> Self {
+        Self {
+            real,
+            sigma: VecSimd::new_from_slice(sigma),
+        }
+    }
+}
+
+impl PartialEq for DynDual {
+    fn eq(&self, other: &Self) -> bool {
+        self.real.eq(&other.real)
+    }
+}
+
+impl PartialOrd for DynDual {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.real.partial_cmp(&other.real)
+    }
+}
+
+impl Eq for DynDual {}
+
+impl PartialEq<f32> for DynDual {
+    fn eq(&self, other: &f32) -> bool {
+        self.real.eq(other)
+    }
+}
+
+impl PartialOrd<f32> for DynDual {
+    fn partial_cmp(&self, other: &f32) -> Option<std::cmp::Ordering> {
+        self.real.partial_cmp(other)
+    }
+}
+
+impl From<DynDual> for f32 {
+    fn from(value: DynDual) -> Self {
+        value.get_real()
+    }
+}
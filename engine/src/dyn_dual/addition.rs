@@ -0,0 +1,24 @@
+use std::ops::Add;
+
+use super::DynDual;
+
+impl Add<DynDual> for DynDual {
+    type Output = DynDual;
+
+    fn add(mut self, rhs: DynDual) -> Self::Output {
+        self.real += rhs.real;
+        self.sigma.acumulate(&rhs.sigma);
+
+        self
+    }
+}
+
+impl Add<f32> for DynDual {
+    type Output = DynDual;
+
+    fn add(mut self, rhs: f32) -> Self::Output {
+        self.real += rhs;
+
+        self
+    }
+}
@@ -0,0 +1,33 @@
+use std::ops::Div;
+
+use super::DynDual;
+
+impl Div<DynDual> for DynDual {
+    type Output = DynDual;
+
+    fn div(mut self, mut rhs: DynDual) -> Self::Output {
+        self.sigma.multiply(rhs.real);
+        rhs.sigma.multiply(self.real);
+
+        rhs.sigma.neg();
+        self.sigma.acumulate(&rhs.sigma);
+
+        self.sigma.multiply(1. / (rhs.real * rhs.real));
+
+        self.real /= rhs.real;
+
+        self
+    }
+}
+
+impl Div<f32> for DynDual {
+    type Output = DynDual;
+
+    fn div(mut self, rhs: f32) -> Self::Output {
+        self.real /= rhs;
+
+        self.sigma.multiply(1. / rhs);
+
+        self
+    }
+}
@@ -0,0 +1,220 @@
+use crate::{
+    dual::extended_arithmetic::{derivatives, ExtendedArithmetic},
+    simd_arr::vec_simd::VecSimd,
+};
+
+use super::DynDual;
+
+impl DynDual {
+    // applies a function given its value and derivative at real
+    fn chain(&mut self, value: f32, derivative: f32) {
+        self.real = value;
+        self.sigma.multiply(derivative);
+    }
+}
+
+impl ExtendedArithmetic for DynDual {
+    fn sqrt(mut self) -> Self {
+        self.sqrt_on_mut();
+        self
+    }
+
+    fn neg(mut self) -> Self {
+        self.neg_on_mut();
+        self
+    }
+
+    fn exp(mut self) -> Self {
+        self.exp_on_mut();
+        self
+    }
+
+    fn pow2(mut self) -> Self {
+        self.pow2_on_mut();
+        self
+    }
+
+    fn abs(mut self) -> Self {
+        self.abs_on_mut();
+        self
+    }
+
+    fn relu(mut self) -> Self {
+        self.relu_on_mut();
+        self
+    }
+
+    fn sigmoid(mut self) -> Self {
+        self.sigmoid_on_mut();
+        self
+    }
+
+    fn ln(mut self) -> Self {
+        self.ln_on_mut();
+        self
+    }
+
+    fn powf(mut self, n: f32) -> Self {
+        self.powf_on_mut(n);
+        self
+    }
+
+    fn powi(mut self, n: i32) -> Self {
+        self.powi_on_mut(n);
+        self
+    }
+
+    fn tanh(mut self) -> Self {
+        self.tanh_on_mut();
+        self
+    }
+
+    fn sin(mut self) -> Self {
+        self.sin_on_mut();
+        self
+    }
+
+    fn cos(mut self) -> Self {
+        self.cos_on_mut();
+        self
+    }
+
+    fn softplus(mut self) -> Self {
+        self.softplus_on_mut();
+        self
+    }
+
+    fn leaky_relu(mut self, slope: f32) -> Self {
+        self.leaky_relu_on_mut(slope);
+        self
+    }
+
+    fn gelu(mut self) -> Self {
+        self.gelu_on_mut();
+        self
+    }
+
+    fn min(self, other: Self) -> Self {
+        if other.real < self.real {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other.real > self.real {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn atan2(mut self, other: Self) -> Self {
+        let [value, dy, dx, ..] = derivatives::atan2(self.real, other.real);
+        let mut other_sigma = other.sigma;
+        other_sigma.multiply(dx);
+
+        self.chain(value, dy);
+        self.sigma.acumulate(&other_sigma);
+        self
+    }
+
+    fn sqrt_on_mut(&mut self) {
+        self.real = self.real.sqrt();
+        self.sigma.multiply(1. / (2. * self.real));
+    }
+
+    fn exp_on_mut(&mut self) {
+        self.real = self.real.exp();
+        self.sigma.multiply(self.real);
+    }
+
+    fn neg_on_mut(&mut self) {
+        self.real = -self.real;
+        self.sigma.neg();
+    }
+
+    fn pow2_on_mut(&mut self) {
+        self.sigma.multiply(self.real * 2.);
+        self.real *= self.real;
+    }
+
+    fn abs_on_mut(&mut self) {
+        if self.real < 0. {
+            self.real = -self.real;
+            self.sigma.neg();
+        }
+    }
+
+    fn sigmoid_on_mut(&mut self) {
+        self.real = self.real.sigmoid();
+
+        self.sigma.multiply(self.real * (1. - self.real));
+    }
+
+    fn relu_on_mut(&mut self) {
+        if self.real < 0. {
+            self.real = 0.;
+            self.sigma = VecSimd::zero();
+        }
+    }
+
+    fn ln_on_mut(&mut self) {
+        self.sigma.multiply(1. / self.real);
+        self.real = self.real.ln();
+    }
+
+    fn powf_on_mut(&mut self, n: f32) {
+        let [value, derivative, _] = derivatives::powf(self.real, n);
+        self.chain(value, derivative);
+    }
+
+    fn powi_on_mut(&mut self, n: i32) {
+        let [value, derivative, _] = derivatives::powi(self.real, n);
+        self.chain(value, derivative);
+    }
+
+    fn tanh_on_mut(&mut self) {
+        let [value, derivative, _] = derivatives::tanh(self.real);
+        self.chain(value, derivative);
+    }
+
+    fn sin_on_mut(&mut self) {
+        let [value, derivative, _] = derivatives::sin(self.real);
+        self.chain(value, derivative);
+    }
+
+    fn cos_on_mut(&mut self) {
+        let [value, derivative, _] = derivatives::cos(self.real);
+        self.chain(value, derivative);
+    }
+
+    fn softplus_on_mut(&mut self) {
+        let [value, derivative, _] = derivatives::softplus(self.real);
+        self.chain(value, derivative);
+    }
+
+    fn leaky_relu_on_mut(&mut self, slope: f32) {
+        let [value, derivative, _] = derivatives::leaky_relu(self.real, slope);
+        self.chain(value, derivative);
+    }
+
+    fn gelu_on_mut(&mut self) {
+        let [value, derivative, _] = derivatives::gelu(self.real);
+        self.chain(value, derivative);
+    }
+
+    fn accumulate(&mut self, x: &DynDual) {
+        self.real += x.real;
+        self.sigma.acumulate(&x.sigma);
+    }
+
+    fn accumulate_product(&mut self, a: &DynDual, b: &DynDual) {
+        self.accumulate(&(a.clone() * b.clone()));
+    }
+
+    fn is_finite(&self) -> bool {
+        DynDual::is_finite(self)
+    }
+}
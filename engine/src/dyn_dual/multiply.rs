@@ -0,0 +1,30 @@
+use std::ops::Mul;
+
+use super::DynDual;
+
+impl Mul<DynDual> for DynDual {
+    type Output = DynDual;
+
+    fn mul(mut self, mut rhs: DynDual) -> Self::Output {
+        self.sigma.multiply(rhs.real);
+        rhs.sigma.multiply(self.real);
+
+        self.real *= rhs.real;
+
+        self.sigma.acumulate(&rhs.sigma);
+
+        self
+    }
+}
+
+impl Mul<f32> for DynDual {
+    type Output = DynDual;
+
+    fn mul(mut self, rhs: f32) -> Self::Output {
+        self.real *= rhs;
+
+        self.sigma.multiply(rhs);
+
+        self
+    }
+}
@@ -0,0 +1,25 @@
+use std::ops::Sub;
+
+use super::DynDual;
+
+impl Sub<DynDual> for DynDual {
+    type Output = DynDual;
+
+    fn sub(mut self, mut rhs: DynDual) -> Self::Output {
+        self.real -= rhs.real;
+        rhs.sigma.neg();
+        self.sigma.acumulate(&rhs.sigma);
+
+        self
+    }
+}
+
+impl Sub<f32> for DynDual {
+    type Output = DynDual;
+
+    fn sub(mut self, rhs: f32) -> Self::Output {
+        self.real -= rhs;
+
+        self
+    }
+}
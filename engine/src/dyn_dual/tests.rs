@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod dyn_dual_tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{
+        dual::extended_arithmetic::ExtendedArithmetic, dyn_dual::DynDual, reverse::Reverse,
+    };
+
+    #[test]
+    fn create() {
+        let dual = DynDual::zero();
+
+        assert_eq!(dual.get_real(), 0.);
+        assert_eq!(dual.get_gradient(3), vec![0.; 3]);
+    }
+
+    #[test]
+    fn matches_reverse() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+        for _ in 0..1000 {
+            let a = rng.gen::<f32>() + 0.5;
+            let b = rng.gen::<f32>() + 0.5;
+
+            let dual = {
+                let (a, b) = (DynDual::new_param(a, 0), DynDual::new_param(b, 2));
+                ((a.clone() * b.clone()).sqrt() + (a.clone() - b.clone()).pow2().exp()) / b.clone()
+                    - (a.clone() / b.clone()).ln().sigmoid() * a.clone().abs()
+                    + (a - b).relu()
+            };
+            let reverse = {
+                let (a, b) = (Reverse::new_param(a, 0), Reverse::new_param(b, 2));
+                ((a.clone() * b.clone()).sqrt() + (a.clone() - b.clone()).pow2().exp()) / b.clone()
+                    - (a.clone() / b.clone()).ln().sigmoid() * a.clone().abs()
+                    + (a - b).relu()
+            };
+
+            assert_eq!(dual.get_real(), reverse.get_real());
+
+            let mut reverse_gradient = vec![0.; 3];
+            reverse.write_gradient(&mut reverse_gradient);
+
+            for (d, r) in dual.get_gradient(3).into_iter().zip(reverse_gradient) {
+                assert!((d - r).abs() < 1e-4 * (1. + r.abs()), "{d} vs {r}");
+            }
+        }
+    }
+}
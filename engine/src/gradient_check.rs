@@ -0,0 +1,180 @@
+use crate::{dual::Dual, scalar::Scalar, simd_arr::SimdArr};
+
+// Jacobian of a model as given by its dual numbers next to the one estimated with central
+// finite differences of its plain scalar version
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientCheck<const P: usize, const O: usize, T: Scalar = f32> {
+    // analytic[output][param]
+    pub analytic: [[T; P]; O],
+    // numeric[output][param]
+    pub numeric: [[T; P]; O],
+}
+
+impl<const P: usize, const O: usize, T: Scalar> GradientCheck<P, O, T> {
+    // the error is relative for derivatives bigger than 1 and absolute otherwise
+    pub fn error(&self, output: usize, param: usize) -> T {
+        let analytic = self.analytic[output][param];
+        let numeric = self.numeric[output][param];
+
+        (analytic - numeric).abs() / T::ONE.max(analytic.abs()).max(numeric.abs())
+    }
+
+    // (output, param) of the biggest error, None if the model has no outputs or params
+    pub fn worst(&self) -> Option<(usize, usize)> {
+        (0..O)
+            .flat_map(|output| (0..P).map(move |param| (output, param)))
+            .max_by(|a, b| {
+                let (a, b) = (self.error(a.0, a.1), self.error(b.0, b.1));
+                // a NaN error is the worst one
+                a.partial_cmp(&b)
+                    .unwrap_or_else(|| b.is_finite().cmp(&a.is_finite()))
+            })
+    }
+
+    pub fn max_error(&self) -> T {
+        self.worst()
+            .map(|(output, param)| self.error(output, param))
+            .unwrap_or(T::ZERO)
+    }
+
+    // a non finite derivative anywhere fails the check
+    pub fn passes(&self, tolerance: T) -> bool {
+        self.worst()
+            .map(|(output, param)| self.error(output, param) <= tolerance)
+            .unwrap_or(true)
+            && self.analytic.iter().flatten().all(|x| x.is_finite())
+            && self.numeric.iter().flatten().all(|x| x.is_finite())
+    }
+}
+
+// Takes the same two functions a Trainer does. `epsilon` is the finite differences step, with f32
+// anything much smaller than 1e-3 is noisy.
+pub fn gradient_check<
+    const P: usize,
+    const I: usize,
+    const O: usize,
+    ExtraData,
+    S: SimdArr<P>,
+    FG: Fn(&[Dual<P, S>; P], &[S::Scalar; I], &ExtraData) -> [Dual<P, S>; O],
+    F: Fn(&[S::Scalar; P], &[S::Scalar; I], &ExtraData) -> [S::Scalar; O],
+>(
+    model_gradient: FG,
+    model: F,
+    params: &[S::Scalar; P],
+    input: &[S::Scalar; I],
+    extra_data: &ExtraData,
+    epsilon: S::Scalar,
+) -> GradientCheck<P, O, S::Scalar> {
+    let dual_params = std::array::from_fn(|i| Dual::new_param(params[i], i));
+    let outputs = model_gradient(&dual_params, input, extra_data);
+
+    let mut numeric = [[S::Scalar::ZERO; P]; O];
+    for param in 0..P {
+        let mut forward = *params;
+        forward[param] += epsilon;
+        let mut backward = *params;
+        backward[param] -= epsilon;
+
+        let forward = model(&forward, input, extra_data);
+        let backward = model(&backward, input, extra_data);
+
+        for output in 0..O {
+            numeric[output][param] = (forward[output] - backward[output]) / (epsilon + epsilon);
+        }
+    }
+
+    GradientCheck {
+        analytic: outputs.map(|x| x.get_gradient()),
+        numeric,
+    }
+}
+
+#[cfg(test)]
+mod gradient_check_tests {
+    use std::ops::{Add, Mul};
+
+    use crate::{
+        dual::extended_arithmetic::ExtendedArithmetic,
+        simd_arr::{dense_simd::DenseSimd, hybrid_simd::HybridSimd},
+    };
+
+    use super::gradient_check;
+
+    fn model<
+        T: Copy,
+        N: Clone + ExtendedArithmetic + Add<N, Output = N> + Mul<N, Output = N> + Mul<T, Output = N>,
+    >(
+        params: &[N; 3],
+        input: &[T; 2],
+        _: &(),
+    ) -> [N; 2] {
+        [
+            (params[0].clone() * input[0] + params[1].clone() * input[1]).tanh(),
+            (params[1].clone() * params[2].clone()).sqrt() + params[0].clone().pow2(),
+        ]
+    }
+
+    #[test]
+    fn accepts_correct_derivatives() {
+        let params = [0.3, 1.2, 0.8];
+        let input = [0.5, -1.5];
+
+        let dense = gradient_check::<_, _, _, _, DenseSimd<3>, _, _>(
+            model,
+            model,
+            &params,
+            &input,
+            &(),
+            1e-2,
+        );
+        let hybrid = gradient_check::<_, _, _, _, HybridSimd<3, 1>, _, _>(
+            model,
+            model,
+            &params,
+            &input,
+            &(),
+            1e-2,
+        );
+
+        assert!(dense.passes(1e-3), "{dense:?}");
+        assert_eq!(dense, hybrid);
+    }
+
+    #[test]
+    fn f64_allows_a_tighter_tolerance() {
+        let check = gradient_check::<_, _, _, _, DenseSimd<3, f64>, _, _>(
+            model,
+            model,
+            &[0.3, 1.2, 0.8],
+            &[0.5, -1.5],
+            &(),
+            1e-5,
+        );
+
+        assert!(check.passes(1e-8), "{check:?}");
+    }
+
+    #[test]
+    fn finds_wrong_derivatives() {
+        // the dual version of the second output is missing a factor of 2
+        let check = gradient_check::<_, _, _, _, DenseSimd<3>, _, _>(
+            |params, input, extra| {
+                let mut ret = model(params, input, extra);
+                ret[1] = params[0].clone().pow2();
+                ret
+            },
+            |params, input, extra| {
+                let mut ret = model(params, input, extra);
+                ret[1] = params[0].powi(2) * 2.;
+                ret
+            },
+            &[0.3, 1.2, 0.8],
+            &[0.5, -1.5],
+            &(),
+            1e-2,
+        );
+
+        assert!(!check.passes(1e-3));
+        assert_eq!(check.worst(), Some((1, 0)));
+    }
+}
@@ -0,0 +1,169 @@
+pub mod addition;
+pub mod division;
+pub mod extended_arithmetic;
+pub mod multiply;
+pub mod substraction;
+mod tests;
+
+use crate::dual::extended_arithmetic::ExtendedArithmetic;
+use crate::scalar::Scalar;
+use crate::simd_arr::SimdArr;
+
+// Dual number over dual numbers. On top of the gradient it tracks the derivative along a fixed
+// direction `v` (tau) and the gradient of that derivative (sigma_tau), which is the Hessian times
+// `v`. Every operation is f(x) = f(real) + f'(real) * dx + f''(real) * dx * dt. The scalar is the
+// one of the SimdArr, like in Dual.
+#[derive(Clone, Debug)]
+pub struct HyperDual<const P: usize, S: SimdArr<P>> {
+    real: S::Scalar,
+    sigma: S,
+    tau: S::Scalar,
+    sigma_tau: S,
+}
+
+impl<const P: usize, S: SimdArr<P>> From<f32> for HyperDual<P, S> {
+    fn from(value: f32) -> Self {
+        Self::new(value.into())
+    }
+}
+
+impl<const P: usize, S: SimdArr<P>> HyperDual<P, S> {
+    // `direction` is the component i of the vector the Hessian gets multiplied by
+    pub fn new_param(real: S::Scalar, i: usize, direction: S::Scalar) -> Self {
+        Self {
+            real,
+            sigma: S::new_from_value_and_pos(S::Scalar::ONE, i),
+            tau: direction,
+            sigma_tau: S::zero(),
+        }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.real.is_finite()
+            && self.sigma.is_finite()
+            && self.tau.is_finite()
+            && self.sigma_tau.is_finite()
+    }
+
+    pub fn zero() -> Self {
+        Self {
+            real: S::Scalar::ZERO,
+            sigma: S::zero(),
+            tau: S::Scalar::ZERO,
+            sigma_tau: S::zero(),
+        }
+    }
+
+    pub fn new(real: S::Scalar) -> Self {
+        let mut ret = Self::zero();
+        ret.real = real;
+        ret
+    }
+
+    pub fn get_real(&self) -> S::Scalar {
+        self.real
+    }
+
+    pub fn get_gradient(&self) -> [S::Scalar; P] {
+        self.sigma.to_array()
+    }
+
+    // gradient dot direction
+    pub fn get_directional_derivative(&self) -> S::Scalar {
+        self.tau
+    }
+
+    pub fn get_hessian_vector_product(&self) -> [S::Scalar; P] {
+        self.sigma_tau.to_array()
+    }
+
+    // applies a function given its value and first and second derivatives at real
+    fn chain(&mut self, value: S::Scalar, derivative: S::Scalar, second_derivative: S::Scalar) {
+        let mut curvature = self.sigma.clone();
+        curvature.multiply(second_derivative * self.tau);
+
+        self.sigma_tau.multiply(derivative);
+        self.sigma_tau.acumulate(&curvature);
+
+        self.sigma.multiply(derivative);
+        self.tau *= derivative;
+        self.real = value;
+    }
+
+    // same as chain for a function of two numbers, second_derivatives are the lhs lhs, lhs rhs
+    // and rhs rhs partials
+    fn binary_chain(
+        value: S::Scalar,
+        lhs: &Self,
+        lhs_derivative: S::Scalar,
+        rhs: &Self,
+        rhs_derivative: S::Scalar,
+        [lhs_lhs, lhs_rhs, rhs_rhs]: [S::Scalar; 3],
+    ) -> Self {
+        let scaled = |x: &S, factor: S::Scalar| {
+            let mut ret = x.clone();
+            ret.multiply(factor);
+            ret
+        };
+
+        let mut sigma = scaled(&lhs.sigma, lhs_derivative);
+        sigma.acumulate(&scaled(&rhs.sigma, rhs_derivative));
+
+        let mut sigma_tau = scaled(&lhs.sigma_tau, lhs_derivative);
+        sigma_tau.acumulate(&scaled(&rhs.sigma_tau, rhs_derivative));
+        sigma_tau.acumulate(&scaled(&lhs.sigma, lhs_lhs * lhs.tau + lhs_rhs * rhs.tau));
+        sigma_tau.acumulate(&scaled(&rhs.sigma, lhs_rhs * lhs.tau + rhs_rhs * rhs.tau));
+
+        Self {
+            real: value,
+            sigma,
+            tau: lhs_derivative * lhs.tau + rhs_derivative * rhs.tau,
+            sigma_tau,
+        }
+    }
+
+    fn scale(&mut self, factor: S::Scalar) {
+        self.real *= factor;
+        self.sigma.multiply(factor);
+        self.tau *= factor;
+        self.sigma_tau.multiply(factor);
+    }
+}
+
+impl<const P: usize, S: SimdArr<P>> PartialEq for HyperDual<P, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.real.eq(&other.real)
+    }
+}
+
+impl<const P: usize, S: SimdArr<P>> PartialOrd for HyperDual<P, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.real.partial_cmp(&other.real)
+    }
+}
+
+// one impl per scalar type, see the ones of Dual
+macro_rules! scalar_comparisons {
+    ($t:ty) => {
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> PartialEq<$t> for HyperDual<P, S> {
+            fn eq(&self, other: &$t) -> bool {
+                self.real.eq(other)
+            }
+        }
+
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> PartialOrd<$t> for HyperDual<P, S> {
+            fn partial_cmp(&self, other: &$t) -> Option<std::cmp::Ordering> {
+                self.real.partial_cmp(other)
+            }
+        }
+
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> From<HyperDual<P, S>> for $t {
+            fn from(value: HyperDual<P, S>) -> Self {
+                value.get_real()
+            }
+        }
+    };
+}
+
+scalar_comparisons!(f32);
+scalar_comparisons!(f64);
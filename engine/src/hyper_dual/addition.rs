@@ -0,0 +1,36 @@
+use std::ops::Add;
+
+use crate::simd_arr::SimdArr;
+
+use super::HyperDual;
+
+impl<const P: usize, S: SimdArr<P>> Add<HyperDual<P, S>> for HyperDual<P, S> {
+    type Output = HyperDual<P, S>;
+
+    fn add(mut self, rhs: HyperDual<P, S>) -> Self::Output {
+        self.real += rhs.real;
+        self.sigma.acumulate(&rhs.sigma);
+        self.tau += rhs.tau;
+        self.sigma_tau.acumulate(&rhs.sigma_tau);
+
+        self
+    }
+}
+
+// one impl per scalar type, see the ones of Dual
+macro_rules! add_scalar {
+    ($t:ty) => {
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> Add<$t> for HyperDual<P, S> {
+            type Output = HyperDual<P, S>;
+
+            fn add(mut self, rhs: $t) -> Self::Output {
+                self.real += rhs;
+
+                self
+            }
+        }
+    };
+}
+
+add_scalar!(f32);
+add_scalar!(f64);
@@ -0,0 +1,37 @@
+use std::ops::Div;
+
+use crate::scalar::Scalar;
+use crate::simd_arr::SimdArr;
+
+use super::HyperDual;
+
+impl<const P: usize, S: SimdArr<P>> Div<HyperDual<P, S>> for HyperDual<P, S> {
+    type Output = HyperDual<P, S>;
+
+    fn div(self, mut rhs: HyperDual<P, S>) -> Self::Output {
+        let x = rhs.real;
+        let inverse = S::Scalar::ONE / x;
+        let inverse2 = inverse * inverse;
+        rhs.chain(inverse, -inverse2, (inverse2 + inverse2) * inverse);
+
+        self * rhs
+    }
+}
+
+// one impl per scalar type, see the ones of Dual
+macro_rules! div_scalar {
+    ($t:ty) => {
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> Div<$t> for HyperDual<P, S> {
+            type Output = HyperDual<P, S>;
+
+            fn div(mut self, rhs: $t) -> Self::Output {
+                self.scale(1. / rhs);
+
+                self
+            }
+        }
+    };
+}
+
+div_scalar!(f32);
+div_scalar!(f64);
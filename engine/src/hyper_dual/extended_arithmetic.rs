@@ -0,0 +1,212 @@
+use crate::{
+    dual::extended_arithmetic::{derivatives, ExtendedArithmetic},
+    scalar::Scalar,
+    simd_arr::SimdArr,
+};
+
+use super::HyperDual;
+
+impl<const P: usize, S: SimdArr<P>> ExtendedArithmetic for HyperDual<P, S> {
+    fn sqrt(mut self) -> Self {
+        self.sqrt_on_mut();
+        self
+    }
+
+    fn neg(mut self) -> Self {
+        self.neg_on_mut();
+        self
+    }
+
+    fn exp(mut self) -> Self {
+        self.exp_on_mut();
+        self
+    }
+
+    fn pow2(mut self) -> Self {
+        self.pow2_on_mut();
+        self
+    }
+
+    fn abs(mut self) -> Self {
+        self.abs_on_mut();
+        self
+    }
+
+    fn relu(mut self) -> Self {
+        self.relu_on_mut();
+        self
+    }
+
+    fn sigmoid(mut self) -> Self {
+        self.sigmoid_on_mut();
+        self
+    }
+
+    fn ln(mut self) -> Self {
+        self.ln_on_mut();
+        self
+    }
+
+    fn powf(mut self, n: f32) -> Self {
+        self.powf_on_mut(n);
+        self
+    }
+
+    fn powi(mut self, n: i32) -> Self {
+        self.powi_on_mut(n);
+        self
+    }
+
+    fn tanh(mut self) -> Self {
+        self.tanh_on_mut();
+        self
+    }
+
+    fn sin(mut self) -> Self {
+        self.sin_on_mut();
+        self
+    }
+
+    fn cos(mut self) -> Self {
+        self.cos_on_mut();
+        self
+    }
+
+    fn softplus(mut self) -> Self {
+        self.softplus_on_mut();
+        self
+    }
+
+    fn leaky_relu(mut self, slope: f32) -> Self {
+        self.leaky_relu_on_mut(slope);
+        self
+    }
+
+    fn gelu(mut self) -> Self {
+        self.gelu_on_mut();
+        self
+    }
+
+    fn min(self, other: Self) -> Self {
+        if other.real < self.real {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other.real > self.real {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        let [value, dy, dx, dyy, dyx, dxx] = derivatives::atan2(self.real, other.real);
+        Self::binary_chain(value, &self, dy, &other, dx, [dyy, dyx, dxx])
+    }
+
+    fn sqrt_on_mut(&mut self) {
+        let root = self.real.sqrt();
+        let half = S::Scalar::from(0.5);
+        self.chain(root, half / root, -half * half / (root * self.real));
+    }
+
+    fn exp_on_mut(&mut self) {
+        let exp = self.real.exp();
+        self.chain(exp, exp, exp);
+    }
+
+    fn neg_on_mut(&mut self) {
+        self.scale(-S::Scalar::ONE);
+    }
+
+    fn pow2_on_mut(&mut self) {
+        let two = S::Scalar::from(2.);
+        self.chain(self.real * self.real, two * self.real, two);
+    }
+
+    fn abs_on_mut(&mut self) {
+        if self.real < S::Scalar::ZERO {
+            self.scale(-S::Scalar::ONE);
+        }
+    }
+
+    fn sigmoid_on_mut(&mut self) {
+        let sigmoid = self.real.sigmoid();
+        let derivative = sigmoid * (S::Scalar::ONE - sigmoid);
+        self.chain(
+            sigmoid,
+            derivative,
+            derivative * (S::Scalar::ONE - sigmoid - sigmoid),
+        );
+    }
+
+    fn relu_on_mut(&mut self) {
+        if self.real < S::Scalar::ZERO {
+            *self = Self::zero();
+        }
+    }
+
+    fn ln_on_mut(&mut self) {
+        let x = self.real;
+        self.chain(x.ln(), S::Scalar::ONE / x, -S::Scalar::ONE / (x * x));
+    }
+
+    fn powf_on_mut(&mut self, n: f32) {
+        let [value, derivative, second_derivative] = derivatives::powf(self.real, n);
+        self.chain(value, derivative, second_derivative);
+    }
+
+    fn powi_on_mut(&mut self, n: i32) {
+        let [value, derivative, second_derivative] = derivatives::powi(self.real, n);
+        self.chain(value, derivative, second_derivative);
+    }
+
+    fn tanh_on_mut(&mut self) {
+        let [value, derivative, second_derivative] = derivatives::tanh(self.real);
+        self.chain(value, derivative, second_derivative);
+    }
+
+    fn sin_on_mut(&mut self) {
+        let [value, derivative, second_derivative] = derivatives::sin(self.real);
+        self.chain(value, derivative, second_derivative);
+    }
+
+    fn cos_on_mut(&mut self) {
+        let [value, derivative, second_derivative] = derivatives::cos(self.real);
+        self.chain(value, derivative, second_derivative);
+    }
+
+    fn softplus_on_mut(&mut self) {
+        let [value, derivative, second_derivative] = derivatives::softplus(self.real);
+        self.chain(value, derivative, second_derivative);
+    }
+
+    fn leaky_relu_on_mut(&mut self, slope: f32) {
+        let [value, derivative, second_derivative] = derivatives::leaky_relu(self.real, slope);
+        self.chain(value, derivative, second_derivative);
+    }
+
+    fn gelu_on_mut(&mut self) {
+        let [value, derivative, second_derivative] = derivatives::gelu(self.real);
+        self.chain(value, derivative, second_derivative);
+    }
+
+    fn accumulate(&mut self, x: &HyperDual<P, S>) {
+        self.real += x.real;
+        self.sigma.acumulate(&x.sigma);
+        self.tau += x.tau;
+        self.sigma_tau.acumulate(&x.sigma_tau);
+    }
+
+    fn accumulate_product(&mut self, a: &HyperDual<P, S>, b: &HyperDual<P, S>) {
+        self.accumulate(&(a.clone() * b.clone()));
+    }
+
+    fn is_finite(&self) -> bool {
+        HyperDual::is_finite(self)
+    }
+}
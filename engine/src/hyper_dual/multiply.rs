@@ -0,0 +1,56 @@
+use std::ops::Mul;
+
+use crate::simd_arr::SimdArr;
+
+use super::HyperDual;
+
+impl<const P: usize, S: SimdArr<P>> Mul<HyperDual<P, S>> for HyperDual<P, S> {
+    type Output = HyperDual<P, S>;
+
+    // (a + a't)(b + b't) = ab + (ab' + a'b)t where a, b, a' and b' are first order duals
+    fn mul(mut self, rhs: HyperDual<P, S>) -> Self::Output {
+        let mut sigma_tau = self.sigma_tau;
+        sigma_tau.multiply(rhs.real);
+
+        let mut rhs_sigma_tau = rhs.sigma_tau;
+        rhs_sigma_tau.multiply(self.real);
+        sigma_tau.acumulate(&rhs_sigma_tau);
+
+        let mut cross = self.sigma.clone();
+        cross.multiply(rhs.tau);
+        sigma_tau.acumulate(&cross);
+
+        let mut cross = rhs.sigma.clone();
+        cross.multiply(self.tau);
+        sigma_tau.acumulate(&cross);
+
+        let mut rhs_sigma = rhs.sigma;
+        rhs_sigma.multiply(self.real);
+        self.sigma.multiply(rhs.real);
+        self.sigma.acumulate(&rhs_sigma);
+
+        self.tau = self.tau * rhs.real + self.real * rhs.tau;
+        self.real *= rhs.real;
+        self.sigma_tau = sigma_tau;
+
+        self
+    }
+}
+
+// one impl per scalar type, see the ones of Dual
+macro_rules! mul_scalar {
+    ($t:ty) => {
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> Mul<$t> for HyperDual<P, S> {
+            type Output = HyperDual<P, S>;
+
+            fn mul(mut self, rhs: $t) -> Self::Output {
+                self.scale(rhs);
+
+                self
+            }
+        }
+    };
+}
+
+mul_scalar!(f32);
+mul_scalar!(f64);
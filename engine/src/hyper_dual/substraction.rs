@@ -0,0 +1,38 @@
+use std::ops::Sub;
+
+use crate::simd_arr::SimdArr;
+
+use super::HyperDual;
+
+impl<const P: usize, S: SimdArr<P>> Sub<HyperDual<P, S>> for HyperDual<P, S> {
+    type Output = HyperDual<P, S>;
+
+    fn sub(mut self, mut rhs: HyperDual<P, S>) -> Self::Output {
+        self.real -= rhs.real;
+        rhs.sigma.neg();
+        self.sigma.acumulate(&rhs.sigma);
+        self.tau -= rhs.tau;
+        rhs.sigma_tau.neg();
+        self.sigma_tau.acumulate(&rhs.sigma_tau);
+
+        self
+    }
+}
+
+// one impl per scalar type, see the ones of Dual
+macro_rules! sub_scalar {
+    ($t:ty) => {
+        impl<const P: usize, S: SimdArr<P, Scalar = $t>> Sub<$t> for HyperDual<P, S> {
+            type Output = HyperDual<P, S>;
+
+            fn sub(mut self, rhs: $t) -> Self::Output {
+                self.real -= rhs;
+
+                self
+            }
+        }
+    };
+}
+
+sub_scalar!(f32);
+sub_scalar!(f64);
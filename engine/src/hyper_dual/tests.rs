@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod hyper_dual_tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{
+        dual::extended_arithmetic::ExtendedArithmetic, hyper_dual::HyperDual, reverse::Reverse,
+        simd_arr::dense_simd::DenseSimd,
+    };
+
+    type Hyper = HyperDual<2, DenseSimd<2>>;
+
+    fn reverse_gradient<F: Fn(Reverse, Reverse) -> Reverse>(f: &F, a: f32, b: f32) -> [f32; 2] {
+        f(Reverse::new_param(a, 0), Reverse::new_param(b, 1)).get_gradient()
+    }
+
+    // the Hessian vector product is the derivative of the gradient along the direction
+    fn assert_second_order<F: Fn(Hyper, Hyper) -> Hyper, G: Fn(Reverse, Reverse) -> Reverse>(
+        hyper_fn: F,
+        reverse_fn: G,
+        a: f32,
+        b: f32,
+        direction: [f32; 2],
+    ) {
+        let result = hyper_fn(
+            HyperDual::new_param(a, 0, direction[0]),
+            HyperDual::new_param(b, 1, direction[1]),
+        );
+        let gradient = reverse_gradient(&reverse_fn, a, b);
+
+        for (hyper, reverse) in result.get_gradient().iter().zip(gradient) {
+            assert!((hyper - reverse).abs() < 1e-4 * (1. + reverse.abs()));
+        }
+
+        let directional = gradient[0] * direction[0] + gradient[1] * direction[1];
+        assert!(
+            (result.get_directional_derivative() - directional).abs()
+                < 1e-4 * (1. + directional.abs())
+        );
+
+        let h = 1e-2;
+        let forward = reverse_gradient(&reverse_fn, a + h * direction[0], b + h * direction[1]);
+        let backward = reverse_gradient(&reverse_fn, a - h * direction[0], b - h * direction[1]);
+
+        for i in 0..2 {
+            let expected = (forward[i] - backward[i]) / (2. * h);
+            let hvp = result.get_hessian_vector_product()[i];
+            assert!(
+                (hvp - expected).abs() < 2e-2 * (1. + expected.abs()),
+                "{hvp} vs {expected}"
+            );
+        }
+    }
+
+    // same body for both number types
+    macro_rules! assert_op {
+        (|$a:ident, $b:ident| $body:expr, $x:expr, $y:expr, $direction:expr) => {
+            assert_second_order(
+                |$a: Hyper, $b: Hyper| $body,
+                |$a: Reverse, $b: Reverse| $body,
+                $x,
+                $y,
+                $direction,
+            )
+        };
+    }
+
+    #[test]
+    fn create() {
+        let hyper = Hyper::zero();
+
+        assert_eq!(hyper.get_real(), 0.);
+        assert_eq!(hyper.get_hessian_vector_product(), [0.; 2]);
+    }
+
+    #[test]
+    fn hessian_vector_product_stress() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+        for _ in 0..200 {
+            let a = rng.gen::<f32>() + 0.5;
+            let b = rng.gen::<f32>() + 0.5;
+            let direction = [rng.gen::<f32>() - 0.5, rng.gen::<f32>() - 0.5];
+
+            assert_op!(|a, b| a + b, a, b, direction);
+            assert_op!(|a, b| a - b, a, b, direction);
+            assert_op!(|a, b| a * b, a, b, direction);
+            assert_op!(|a, b| a / b, a, b, direction);
+            assert_op!(|a, b| (a * b).sqrt(), a, b, direction);
+            assert_op!(|a, b| (a.clone() - b).exp() * a, a, b, direction);
+            assert_op!(|a, b| (a * b).pow2(), a, b, direction);
+            assert_op!(|a, b| (a.clone() * b).sigmoid() * a, a, b, direction);
+            assert_op!(|a, b| (a * b).ln(), a, b, direction);
+            assert_op!(|a, b| (a * b).powf(1.5), a, b, direction);
+            assert_op!(|a, b| (a + b).powi(-3), a, b, direction);
+            assert_op!(|a, b| (a.clone() - b).tanh() * a, a, b, direction);
+            assert_op!(|a, b| (a * b).sin(), a, b, direction);
+            assert_op!(|a, b| (a.clone() * b).cos() * a, a, b, direction);
+            assert_op!(|a, b| (a.clone() - b).softplus() * a, a, b, direction);
+            assert_op!(|a, b| (a.clone() - b).gelu() * a, a, b, direction);
+            assert_op!(|a, b| a.atan2(b - 1.), a, b, direction);
+            assert_op!(|a, b| (a.clone() * a).atan2(b.clone() * b), a, b, direction);
+            assert_op!(
+                |a, b| (a.clone() * 3. + 1.) / 2. * a - b * 4.,
+                a,
+                b,
+                direction
+            );
+        }
+
+        // the powers of 0 lose a factor of inf in their derivatives
+        for n in 0..4 {
+            assert_op!(|a, b| a.powi(n) * b, 0., 1.3, [0.3, -0.2]);
+            assert_op!(|a, b| a.powf(n as f32) * b, 0., 1.3, [0.3, -0.2]);
+        }
+    }
+}
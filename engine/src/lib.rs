@@ -1,5 +1,12 @@
-#![feature(generic_arg_infer)]
+#![feature(portable_simd)]
 
+pub mod differentiable;
 pub mod dual;
+pub mod dyn_dual;
+pub mod gradient_check;
+pub mod hyper_dual;
+pub mod metrics;
+pub mod reverse;
+pub mod scalar;
 pub mod simd_arr;
 pub mod trainer;
@@ -0,0 +1,251 @@
+use rayon::prelude::*;
+
+use crate::scalar::Scalar;
+use crate::trainer::dataset::Dataset;
+use crate::trainer::error::TrainError;
+
+// index of the highest value, the class a one hot (or softmax) output stands for
+pub fn argmax<T: PartialOrd>(values: &[T]) -> usize {
+    let mut ret = 0;
+    for (i, x) in values.iter().enumerate() {
+        if *x > values[ret] {
+            ret = i;
+        }
+    }
+    ret
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClassMetrics {
+    pub precision: f32,
+    pub recall: f32,
+    pub f1: f32,
+    // data points of this class in the dataset
+    pub support: usize,
+}
+
+// Classification quality of a model over a dataset, every output is a class and the goal of a
+// data point is the argmax of its output
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassificationReport<const O: usize> {
+    // confusion_matrix[goal][prediction]
+    pub confusion_matrix: [[usize; O]; O],
+    pub top_k: usize,
+    // data points whose goal was among the top_k predictions
+    pub top_k_hits: usize,
+}
+
+impl<const O: usize> ClassificationReport<O> {
+    fn empty(top_k: usize) -> Self {
+        Self {
+            confusion_matrix: [[0; O]; O],
+            top_k,
+            top_k_hits: 0,
+        }
+    }
+
+    fn record<T: Scalar>(mut self, prediction: &[T; O], goal: &[T; O]) -> Self {
+        let goal = argmax(goal);
+        // ranked like argmax, ties before the goal beat it and a non finite goal is never a hit
+        let better = prediction
+            .iter()
+            .enumerate()
+            .filter(|(i, x)| **x > prediction[goal] || (*i < goal && **x == prediction[goal]))
+            .count();
+
+        self.confusion_matrix[goal][argmax(prediction)] += 1;
+        if prediction[goal].is_finite() && better < self.top_k {
+            self.top_k_hits += 1;
+        }
+        self
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        for (row, other_row) in self.confusion_matrix.iter_mut().zip(other.confusion_matrix) {
+            for (x, y) in row.iter_mut().zip(other_row) {
+                *x += y;
+            }
+        }
+        self.top_k_hits += other.top_k_hits;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.confusion_matrix.iter().flatten().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn accuracy(&self) -> f32 {
+        let hits: usize = (0..O).map(|i| self.confusion_matrix[i][i]).sum();
+        ratio(hits, self.len())
+    }
+
+    pub fn top_k_accuracy(&self) -> f32 {
+        ratio(self.top_k_hits, self.len())
+    }
+
+    pub fn class(&self, class: usize) -> ClassMetrics {
+        let hits = self.confusion_matrix[class][class];
+        let predicted: usize = self.confusion_matrix.iter().map(|row| row[class]).sum();
+        let support: usize = self.confusion_matrix[class].iter().sum();
+
+        let precision = ratio(hits, predicted);
+        let recall = ratio(hits, support);
+        let f1 = if precision + recall == 0. {
+            0.
+        } else {
+            2. * precision * recall / (precision + recall)
+        };
+
+        ClassMetrics {
+            precision,
+            recall,
+            f1,
+            support,
+        }
+    }
+
+    pub fn classes(&self) -> [ClassMetrics; O] {
+        std::array::from_fn(|i| self.class(i))
+    }
+}
+
+// 0 when there is nothing to measure
+fn ratio(count: usize, total: usize) -> f32 {
+    if total == 0 {
+        0.
+    } else {
+        count as f32 / total as f32
+    }
+}
+
+// `model` is usually a trainer's eval, ie: `|input| trainer.eval(input)`. Fails when the dataset
+// can't fetch a data point
+pub fn classification_report<
+    const PARALELIZE: bool,
+    const I: usize,
+    const O: usize,
+    T: Scalar,
+    M: Fn(&[T; I]) -> [T; O] + Sync,
+    D: Dataset<I, O, T> + ?Sized,
+>(
+    model: M,
+    dataset: &D,
+    top_k: usize,
+) -> Result<ClassificationReport<O>, TrainError> {
+    let record = |report: ClassificationReport<O>, index| -> Result<_, TrainError> {
+        let data_point = dataset
+            .get(index)
+            .map_err(|err| TrainError::dataset(index, err))?;
+        Ok(report.record(&model(&data_point.input), &data_point.output))
+    };
+
+    if PARALELIZE {
+        (0..dataset.len())
+            .into_par_iter()
+            .try_fold(|| ClassificationReport::empty(top_k), record)
+            .try_reduce(|| ClassificationReport::empty(top_k), |a, b| Ok(a.merge(b)))
+    } else {
+        (0..dataset.len()).try_fold(ClassificationReport::empty(top_k), record)
+    }
+}
+
+#[cfg(test)]
+mod metrics_tests {
+    use super::{argmax, classification_report};
+    use crate::trainer::DataPoint;
+
+    fn one_hot(class: usize) -> [f32; 3] {
+        std::array::from_fn(|i| if i == class { 1. } else { 0. })
+    }
+
+    // the input is the prediction
+    fn dataset() -> Vec<DataPoint<3, 3>> {
+        [
+            ([0.7, 0.2, 0.1], 0),
+            ([0.6, 0.3, 0.1], 0),
+            ([0.2, 0.5, 0.3], 0),
+            ([0.1, 0.8, 0.1], 1),
+            ([0.5, 0.4, 0.1], 1),
+            ([0.1, 0.2, 0.7], 2),
+        ]
+        .map(|(input, class)| DataPoint::new(input, one_hot(class)))
+        .to_vec()
+    }
+
+    #[test]
+    fn known_values() {
+        let report =
+            classification_report::<false, _, _, _, _, _>(|input| *input, &dataset(), 2).unwrap();
+
+        assert_eq!(report.confusion_matrix, [[2, 1, 0], [1, 1, 0], [0, 0, 1]]);
+        assert_eq!(report.accuracy(), 4. / 6.);
+        // [0.2, 0.5, 0.3] ranks its goal last
+        assert_eq!(report.top_k_accuracy(), 5. / 6.);
+
+        let class_0 = report.class(0);
+        assert_eq!(class_0.precision, 2. / 3.);
+        assert_eq!(class_0.recall, 2. / 3.);
+        assert!((class_0.f1 - 2. / 3.).abs() < 1e-6);
+        assert_eq!(class_0.support, 3);
+
+        let class_1 = report.class(1);
+        assert_eq!(class_1.precision, 0.5);
+        assert_eq!(class_1.recall, 0.5);
+
+        assert_eq!(report.class(2).f1, 1.);
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let dataset = dataset().repeat(100);
+
+        assert_eq!(
+            classification_report::<true, _, _, _, _, _>(|input| *input, &dataset, 1).unwrap(),
+            classification_report::<false, _, _, _, _, _>(|input| *input, &dataset, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn f64_matches_f32() {
+        let dataset = dataset();
+        let dataset_f64: Vec<DataPoint<3, 3, f64>> = dataset
+            .iter()
+            .map(|point| DataPoint::new(point.input.map(f64::from), point.output.map(f64::from)))
+            .collect();
+
+        assert_eq!(
+            classification_report::<false, _, _, _, _, _>(|input| *input, &dataset_f64, 2).unwrap(),
+            classification_report::<false, _, _, _, _, _>(|input| *input, &dataset, 2).unwrap()
+        );
+    }
+
+    #[test]
+    fn ties_and_nans_rank_like_argmax() {
+        let dataset = dataset();
+
+        let constant =
+            classification_report::<false, _, _, _, _, _>(|_| [0.5; 3], &dataset, 1).unwrap();
+        assert_eq!(constant.accuracy(), 3. / 6.);
+        assert_eq!(constant.top_k_accuracy(), constant.accuracy());
+
+        let diverged =
+            classification_report::<false, _, _, _, _, _>(|_| [f32::NAN; 3], &dataset, 2).unwrap();
+        assert_eq!(diverged.top_k_accuracy(), 0.);
+    }
+
+    #[test]
+    fn empty_dataset() {
+        let report =
+            classification_report::<true, _, _, _, _, _>(|input| *input, &dataset()[..0], 1)
+                .unwrap();
+
+        assert!(report.is_empty());
+        assert_eq!(report.accuracy(), 0.);
+        assert_eq!(report.class(0).f1, 0.);
+        assert_eq!(argmax(&[0.1, 0.3, 0.2]), 1);
+    }
+}
@@ -0,0 +1,244 @@
+pub mod addition;
+pub mod division;
+pub mod extended_arithmetic;
+pub mod multiply;
+pub mod substraction;
+mod tests;
+
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
+
+// Every operation on a `Reverse` records a node with the local derivative towards each of its
+// operands. The gradient is computed by linearizing the graph reachable from the output into a
+// tape (topological order) and walking it backwards once.
+struct Node {
+    param: Option<usize>,
+    parents: [Option<(Arc<Node>, f32)>; 2],
+    // false if any local derivative up the graph is NaN or infinite
+    finite: bool,
+}
+
+impl Node {
+    fn new(parents: [Option<(Arc<Node>, f32)>; 2]) -> Self {
+        let finite = parents
+            .iter()
+            .flatten()
+            .all(|(p, derivative)| p.finite && derivative.is_finite());
+
+        Self {
+            param: None,
+            parents,
+            finite,
+        }
+    }
+}
+
+impl Drop for Node {
+    // dropping a long chain of nodes recursively would blow the stack
+    fn drop(&mut self) {
+        let mut stack: Vec<Arc<Node>> = self
+            .parents
+            .iter_mut()
+            .filter_map(|p| p.take().map(|(n, _)| n))
+            .collect();
+
+        while let Some(node) = stack.pop() {
+            if let Ok(mut node) = Arc::try_unwrap(node) {
+                stack.extend(
+                    node.parents
+                        .iter_mut()
+                        .filter_map(|p| p.take().map(|(n, _)| n)),
+                );
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Reverse {
+    real: f32,
+    // constants don't take part in the graph
+    node: Option<Arc<Node>>,
+}
+
+impl Debug for Reverse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Reverse")
+            .field("real", &self.real)
+            .field("tracked", &self.node.is_some())
+            .finish()
+    }
+}
+
+impl From<f32> for Reverse {
+    fn from(value: f32) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Reverse {
+    pub fn new(real: f32) -> Self {
+        Self { real, node: None }
+    }
+
+    pub fn new_param(real: f32, i: usize) -> Self {
+        Self {
+            real,
+            node: Some(Arc::new(Node {
+                param: Some(i),
+                parents: [None, None],
+                finite: true,
+            })),
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(0.)
+    }
+
+    pub fn get_real(&self) -> f32 {
+        self.real
+    }
+
+    pub(crate) fn set_real(&mut self, val: f32) {
+        self.real = val
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.real.is_finite() && self.node.as_ref().map(|n| n.finite).unwrap_or(true)
+    }
+
+    // result of an operation with a single operand, `derivative` is d(result)/d(operand)
+    fn unary(real: f32, operand: &Reverse, derivative: f32) -> Self {
+        Self {
+            real,
+            node: operand
+                .node
+                .as_ref()
+                .map(|n| Arc::new(Node::new([Some((n.clone(), derivative)), None]))),
+        }
+    }
+
+    fn binary(
+        real: f32,
+        lhs: &Reverse,
+        lhs_derivative: f32,
+        rhs: &Reverse,
+        rhs_derivative: f32,
+    ) -> Self {
+        let parents = [
+            lhs.node.as_ref().map(|n| (n.clone(), lhs_derivative)),
+            rhs.node.as_ref().map(|n| (n.clone(), rhs_derivative)),
+        ];
+
+        Self {
+            real,
+            node: if parents.iter().any(Option::is_some) {
+                Some(Arc::new(Node::new(parents)))
+            } else {
+                None
+            },
+        }
+    }
+
+    // backward pass, accumulates d(self)/d(param i) into gradient[i]
+    pub fn write_gradient(&self, gradient: &mut [f32]) {
+        let Some(root) = &self.node else {
+            return;
+        };
+
+        let tape = linearize(root);
+        let position: HashMap<*const Node, usize> = tape
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (Arc::as_ptr(n), i))
+            .collect();
+
+        let mut adjoints = vec![0.; tape.len()];
+        adjoints[tape.len() - 1] = 1.;
+
+        for (i, node) in tape.iter().enumerate().rev() {
+            let adjoint = adjoints[i];
+            if adjoint == 0. {
+                continue;
+            }
+
+            if let Some(param) = node.param {
+                gradient[param] += adjoint;
+            }
+
+            for (parent, derivative) in node.parents.iter().flatten() {
+                adjoints[position[&Arc::as_ptr(parent)]] += adjoint * derivative;
+            }
+        }
+    }
+
+    pub fn get_gradient<const P: usize>(&self) -> [f32; P] {
+        let mut gradient = [0.; P];
+        self.write_gradient(&mut gradient);
+        gradient
+    }
+}
+
+// topological order of the nodes reachable from root, root last
+fn linearize(root: &Arc<Node>) -> Vec<Arc<Node>> {
+    let mut tape = vec![];
+    let mut visited = std::collections::HashSet::new();
+    let mut stack = vec![(root.clone(), false)];
+
+    while let Some((node, expanded)) = stack.pop() {
+        if expanded {
+            tape.push(node);
+            continue;
+        }
+
+        if !visited.insert(Arc::as_ptr(&node)) {
+            continue;
+        }
+
+        let parents: Vec<_> = node
+            .parents
+            .iter()
+            .flatten()
+            .map(|(p, _)| p.clone())
+            .collect();
+        stack.push((node, true));
+
+        for parent in parents {
+            if !visited.contains(&Arc::as_ptr(&parent)) {
+                stack.push((parent, false));
+            }
+        }
+    }
+
+    tape
+}
+
+impl PartialEq for Reverse {
+    fn eq(&self, other: &Self) -> bool {
+        self.real.eq(&other.real)
+    }
+}
+
+impl PartialOrd for Reverse {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.real.partial_cmp(&other.real)
+    }
+}
+
+impl PartialEq<f32> for Reverse {
+    fn eq(&self, other: &f32) -> bool {
+        self.real.eq(other)
+    }
+}
+
+impl PartialOrd<f32> for Reverse {
+    fn partial_cmp(&self, other: &f32) -> Option<std::cmp::Ordering> {
+        self.real.partial_cmp(other)
+    }
+}
+
+impl From<Reverse> for f32 {
+    fn from(value: Reverse) -> Self {
+        value.get_real()
+    }
+}
@@ -0,0 +1,21 @@
+use std::ops::Add;
+
+use super::Reverse;
+
+impl Add<Reverse> for Reverse {
+    type Output = Reverse;
+
+    fn add(self, rhs: Reverse) -> Self::Output {
+        Reverse::binary(self.real + rhs.real, &self, 1., &rhs, 1.)
+    }
+}
+
+impl Add<f32> for Reverse {
+    type Output = Reverse;
+
+    fn add(mut self, rhs: f32) -> Self::Output {
+        self.real += rhs;
+
+        self
+    }
+}
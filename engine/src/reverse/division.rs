@@ -0,0 +1,25 @@
+use std::ops::Div;
+
+use super::Reverse;
+
+impl Div<Reverse> for Reverse {
+    type Output = Reverse;
+
+    fn div(self, rhs: Reverse) -> Self::Output {
+        Reverse::binary(
+            self.real / rhs.real,
+            &self,
+            1. / rhs.real,
+            &rhs,
+            -self.real / (rhs.real * rhs.real),
+        )
+    }
+}
+
+impl Div<f32> for Reverse {
+    type Output = Reverse;
+
+    fn div(self, rhs: f32) -> Self::Output {
+        Reverse::unary(self.real / rhs, &self, 1. / rhs)
+    }
+}
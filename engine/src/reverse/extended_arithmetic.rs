@@ -0,0 +1,185 @@
+use crate::dual::extended_arithmetic::{derivatives, ExtendedArithmetic};
+
+use super::Reverse;
+
+impl ExtendedArithmetic for Reverse {
+    fn sqrt(self) -> Self {
+        let real = self.real.sqrt();
+        Reverse::unary(real, &self, 1. / (2. * real))
+    }
+
+    fn neg(self) -> Self {
+        Reverse::unary(-self.real, &self, -1.)
+    }
+
+    fn exp(self) -> Self {
+        let real = self.real.exp();
+        Reverse::unary(real, &self, real)
+    }
+
+    fn pow2(self) -> Self {
+        Reverse::unary(self.real * self.real, &self, 2. * self.real)
+    }
+
+    fn abs(self) -> Self {
+        if self.real < 0. {
+            self.neg()
+        } else {
+            self
+        }
+    }
+
+    fn sigmoid(self) -> Self {
+        let real = 1. / (1. + (-self.real).exp());
+        Reverse::unary(real, &self, real * (1. - real))
+    }
+
+    fn relu(self) -> Self {
+        if self.real < 0. {
+            Reverse::zero()
+        } else {
+            self
+        }
+    }
+
+    fn ln(self) -> Self {
+        Reverse::unary(self.real.ln(), &self, 1. / self.real)
+    }
+
+    fn powf(self, n: f32) -> Self {
+        let [value, derivative, _] = derivatives::powf(self.real, n);
+        Reverse::unary(value, &self, derivative)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        let [value, derivative, _] = derivatives::powi(self.real, n);
+        Reverse::unary(value, &self, derivative)
+    }
+
+    fn tanh(self) -> Self {
+        let [value, derivative, _] = derivatives::tanh(self.real);
+        Reverse::unary(value, &self, derivative)
+    }
+
+    fn sin(self) -> Self {
+        let [value, derivative, _] = derivatives::sin(self.real);
+        Reverse::unary(value, &self, derivative)
+    }
+
+    fn cos(self) -> Self {
+        let [value, derivative, _] = derivatives::cos(self.real);
+        Reverse::unary(value, &self, derivative)
+    }
+
+    fn softplus(self) -> Self {
+        let [value, derivative, _] = derivatives::softplus(self.real);
+        Reverse::unary(value, &self, derivative)
+    }
+
+    fn leaky_relu(self, slope: f32) -> Self {
+        let [value, derivative, _] = derivatives::leaky_relu(self.real, slope);
+        Reverse::unary(value, &self, derivative)
+    }
+
+    fn gelu(self) -> Self {
+        let [value, derivative, _] = derivatives::gelu(self.real);
+        Reverse::unary(value, &self, derivative)
+    }
+
+    fn min(self, other: Self) -> Self {
+        if other.real < self.real {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other.real > self.real {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        let [value, dy, dx, ..] = derivatives::atan2(self.real, other.real);
+        Reverse::binary(value, &self, dy, &other, dx)
+    }
+
+    fn sqrt_on_mut(&mut self) {
+        *self = self.clone().sqrt();
+    }
+
+    fn exp_on_mut(&mut self) {
+        *self = self.clone().exp();
+    }
+
+    fn neg_on_mut(&mut self) {
+        *self = self.clone().neg();
+    }
+
+    fn pow2_on_mut(&mut self) {
+        *self = self.clone().pow2();
+    }
+
+    fn abs_on_mut(&mut self) {
+        *self = self.clone().abs();
+    }
+
+    fn sigmoid_on_mut(&mut self) {
+        *self = self.clone().sigmoid();
+    }
+
+    fn relu_on_mut(&mut self) {
+        *self = self.clone().relu();
+    }
+
+    fn ln_on_mut(&mut self) {
+        *self = self.clone().ln();
+    }
+
+    fn powf_on_mut(&mut self, n: f32) {
+        *self = self.clone().powf(n);
+    }
+
+    fn powi_on_mut(&mut self, n: i32) {
+        *self = self.clone().powi(n);
+    }
+
+    fn tanh_on_mut(&mut self) {
+        *self = self.clone().tanh();
+    }
+
+    fn sin_on_mut(&mut self) {
+        *self = self.clone().sin();
+    }
+
+    fn cos_on_mut(&mut self) {
+        *self = self.clone().cos();
+    }
+
+    fn softplus_on_mut(&mut self) {
+        *self = self.clone().softplus();
+    }
+
+    fn leaky_relu_on_mut(&mut self, slope: f32) {
+        *self = self.clone().leaky_relu(slope);
+    }
+
+    fn gelu_on_mut(&mut self) {
+        *self = self.clone().gelu();
+    }
+
+    fn accumulate(&mut self, x: &Reverse) {
+        *self = self.clone() + x.clone();
+    }
+
+    fn accumulate_product(&mut self, a: &Reverse, b: &Reverse) {
+        *self = self.clone() + a.clone() * b.clone();
+    }
+
+    fn is_finite(&self) -> bool {
+        Reverse::is_finite(self)
+    }
+}
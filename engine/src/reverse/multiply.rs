@@ -0,0 +1,19 @@
+use std::ops::Mul;
+
+use super::Reverse;
+
+impl Mul<Reverse> for Reverse {
+    type Output = Reverse;
+
+    fn mul(self, rhs: Reverse) -> Self::Output {
+        Reverse::binary(self.real * rhs.real, &self, rhs.real, &rhs, self.real)
+    }
+}
+
+impl Mul<f32> for Reverse {
+    type Output = Reverse;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        Reverse::unary(self.real * rhs, &self, rhs)
+    }
+}
@@ -0,0 +1,21 @@
+use std::ops::Sub;
+
+use super::Reverse;
+
+impl Sub<Reverse> for Reverse {
+    type Output = Reverse;
+
+    fn sub(self, rhs: Reverse) -> Self::Output {
+        Reverse::binary(self.real - rhs.real, &self, 1., &rhs, -1.)
+    }
+}
+
+impl Sub<f32> for Reverse {
+    type Output = Reverse;
+
+    fn sub(mut self, rhs: f32) -> Self::Output {
+        self.real -= rhs;
+
+        self
+    }
+}
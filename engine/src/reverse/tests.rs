@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod reverse_tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{dual::extended_arithmetic::ExtendedArithmetic, reverse::Reverse};
+
+    fn assert_gradient<F: Fn(Reverse, Reverse) -> Reverse, G: Fn(f32, f32) -> f32>(
+        reverse_fn: F,
+        scalar_fn: G,
+        a: f32,
+        b: f32,
+    ) {
+        let result = reverse_fn(Reverse::new_param(a, 0), Reverse::new_param(b, 1));
+        let gradient = result.get_gradient::<2>();
+
+        assert_eq!(result.get_real(), scalar_fn(a, b));
+
+        let h = 1e-2;
+        let da = (scalar_fn(a + h, b) - scalar_fn(a - h, b)) / (2. * h);
+        let db = (scalar_fn(a, b + h) - scalar_fn(a, b - h)) / (2. * h);
+
+        assert!(
+            (gradient[0] - da).abs() < 1e-2 * (1. + da.abs()),
+            "{gradient:?} vs {da}"
+        );
+        assert!(
+            (gradient[1] - db).abs() < 1e-2 * (1. + db.abs()),
+            "{gradient:?} vs {db}"
+        );
+    }
+
+    #[test]
+    fn create() {
+        let reverse = Reverse::zero();
+
+        assert_eq!(reverse.get_real(), 0.);
+        assert_eq!(reverse.get_gradient::<4>(), [0.; 4]);
+    }
+
+    #[test]
+    fn gradient_stress() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+        for _ in 0..1000 {
+            let a = rng.gen::<f32>() + 0.5;
+            let b = rng.gen::<f32>() + 0.5;
+
+            assert_gradient(|a, b| a + b, |a, b| a + b, a, b);
+            assert_gradient(|a, b| a - b, |a, b| a - b, a, b);
+            assert_gradient(|a, b| a * b, |a, b| a * b, a, b);
+            assert_gradient(|a, b| a / b, |a, b| a / b, a, b);
+            assert_gradient(|a, b| (a * b).sqrt(), |a, b| (a * b).sqrt(), a, b);
+            assert_gradient(|a, b| (a - b).exp(), |a, b| (a - b).exp(), a, b);
+            assert_gradient(|a, b| (a * b).pow2(), |a, b| (a * b).powi(2), a, b);
+            assert_gradient(|a, b| (a - b).sigmoid(), |a, b| (a - b).sigmoid(), a, b);
+            assert_gradient(|a, b| (a * b).ln(), |a, b| (a * b).ln(), a, b);
+            assert_gradient(
+                |a, b| (a * 3. + 1.) / 2. - b,
+                |a, b| (a * 3. + 1.) / 2. - b,
+                a,
+                b,
+            );
+            assert_gradient(|a, b| (a * b).powf(1.5), |a, b| (a * b).powf(1.5), a, b);
+            assert_gradient(|a, b| (a + b).powi(-3), |a, b| (a + b).powi(-3), a, b);
+            assert_gradient(|a, b| (a - b).tanh(), |a, b| (a - b).tanh(), a, b);
+            assert_gradient(|a, b| (a * b).sin(), |a, b| (a * b).sin(), a, b);
+            assert_gradient(|a, b| (a * b).cos(), |a, b| (a * b).cos(), a, b);
+            assert_gradient(|a, b| (a - b).softplus(), |a, b| (a - b).softplus(), a, b);
+            assert_gradient(|a, b| (a - b).gelu(), |a, b| (a - b).gelu(), a, b);
+            assert_gradient(|a, b| a.atan2(b - 1.), |a, b| a.atan2(b - 1.), a, b);
+
+            // keep the finite differences away from the kinks
+            if (a - b).abs() > 0.1 {
+                assert_gradient(
+                    |a, b| (a - b.clone()).abs() * b,
+                    |a, b| (a - b).abs() * b,
+                    a,
+                    b,
+                );
+                assert_gradient(
+                    |a, b| (a.clone() - b).relu() * a,
+                    |a, b| (a - b).max(0.) * a,
+                    a,
+                    b,
+                );
+                assert_gradient(
+                    |a, b| (a.clone() - b).leaky_relu(0.1) * a,
+                    |a, b| (a - b).leaky_relu(0.1) * a,
+                    a,
+                    b,
+                );
+                assert_gradient(
+                    |a, b| ExtendedArithmetic::min(a.clone(), b.clone()) * a,
+                    |a, b| ExtendedArithmetic::min(a, b) * a,
+                    a,
+                    b,
+                );
+                assert_gradient(
+                    |a, b| ExtendedArithmetic::max(a.clone(), b.clone()) * a,
+                    |a, b| ExtendedArithmetic::max(a, b) * a,
+                    a,
+                    b,
+                );
+            }
+        }
+
+        // the powers of 0 lose a factor of inf in their derivatives
+        for b in [0.5, 1.7] {
+            for n in 0..4 {
+                assert_gradient(|a, b| a.powi(n) * b, |a, b| a.powi(n) * b, 0., b);
+                assert_gradient(
+                    |a, b| a.powf(n as f32) * b,
+                    |a, b| a.powf(n as f32) * b,
+                    0.,
+                    b,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn shared_subexpressions() {
+        let a = Reverse::new_param(3., 0);
+        let squared = a.clone() * a.clone();
+        let result = squared.clone() * squared + a;
+
+        // d/da (a^4 + a) = 4a^3 + 1
+        assert_eq!(result.get_gradient::<1>(), [109.]);
+    }
+
+    #[test]
+    fn long_chains() {
+        let a = Reverse::new_param(1., 0);
+        let mut acc = Reverse::zero();
+
+        for _ in 0..100_000 {
+            acc.accumulate(&a);
+        }
+
+        assert_eq!(acc.get_gradient::<1>(), [100_000.]);
+    }
+
+    #[test]
+    fn non_finite_derivatives_are_tracked() {
+        let a = Reverse::new_param(0., 0);
+
+        assert!(a.clone().is_finite());
+        assert!(!a.sqrt().is_finite());
+    }
+}
@@ -0,0 +1,200 @@
+use std::{
+    fmt::Debug,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    simd::{Simd, StdFloat},
+};
+
+use rand::Rng;
+
+use crate::dual::extended_arithmetic::ExtendedArithmetic;
+
+// Floating point type the numbers are built on. f32 is the default everywhere, f64 is there for
+// models that run out of precision (ie: high degree polynomials)
+pub trait Scalar:
+    ExtendedArithmetic
+    + Copy
+    + Debug
+    + Default
+    + Send
+    + Sync
+    + PartialOrd
+    + Sum
+    + From<f32>
+    + Into<f64>
+    + Add<Self, Output = Self>
+    + Sub<Self, Output = Self>
+    + Mul<Self, Output = Self>
+    + Div<Self, Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    // for Index implementations that have to hand out a reference to an implicit zero
+    fn zero_ref() -> &'static Self;
+
+    fn from_f64(x: f64) -> Self;
+
+    fn to_f32(self) -> f32;
+
+    fn ln_1p(self) -> Self;
+
+    // uniform in [0, 1) at the precision of the scalar
+    fn random(rng: &mut impl Rng) -> Self;
+
+    // Slice kernels behind DenseSimd, vectorized with std::simd. The slices of the binary ones
+    // have the same length.
+
+    fn add_assign_slice(lhs: &mut [Self], rhs: &[Self]);
+
+    // lhs += rhs * factor, fused on targets with fma. Without it mul_add is a libm call per element
+    // so the multiplication and the addition are kept apart.
+    fn mul_add_assign_slice(lhs: &mut [Self], rhs: &[Self], factor: Self);
+
+    // lhs = a * lhs + b * rhs, fused like mul_add_assign_slice
+    fn axpy_slice(lhs: &mut [Self], a: Self, rhs: &[Self], b: Self);
+
+    fn scale_slice(values: &mut [Self], factor: Self);
+
+    fn neg_slice(values: &mut [Self]);
+}
+
+// $lanes fills a 256 bit register
+macro_rules! impl_scalar {
+    ($t:ty, $lanes:literal) => {
+        impl Scalar for $t {
+            const ZERO: Self = 0.;
+            const ONE: Self = 1.;
+
+            fn zero_ref() -> &'static Self {
+                &0.
+            }
+
+            fn from_f64(x: f64) -> Self {
+                x as $t
+            }
+
+            fn to_f32(self) -> f32 {
+                self as f32
+            }
+
+            fn ln_1p(self) -> Self {
+                <$t>::ln_1p(self)
+            }
+
+            fn random(rng: &mut impl Rng) -> Self {
+                rng.gen()
+            }
+
+            #[inline]
+            fn add_assign_slice(lhs: &mut [Self], rhs: &[Self]) {
+                let mut lhs_chunks = lhs.chunks_exact_mut($lanes);
+                let mut rhs_chunks = rhs.chunks_exact($lanes);
+
+                for (l, r) in (&mut lhs_chunks).zip(&mut rhs_chunks) {
+                    (Simd::<$t, $lanes>::from_slice(l) + Simd::from_slice(r)).copy_to_slice(l);
+                }
+
+                for (l, r) in lhs_chunks
+                    .into_remainder()
+                    .iter_mut()
+                    .zip(rhs_chunks.remainder())
+                {
+                    *l += *r;
+                }
+            }
+
+            #[inline]
+            fn mul_add_assign_slice(lhs: &mut [Self], rhs: &[Self], factor: Self) {
+                let simd_factor = Simd::<$t, $lanes>::splat(factor);
+                let mut lhs_chunks = lhs.chunks_exact_mut($lanes);
+                let mut rhs_chunks = rhs.chunks_exact($lanes);
+
+                for (l, r) in (&mut lhs_chunks).zip(&mut rhs_chunks) {
+                    let (l_simd, r_simd) = (Simd::from_slice(l), Simd::from_slice(r));
+                    if cfg!(target_feature = "fma") {
+                        r_simd.mul_add(simd_factor, l_simd).copy_to_slice(l);
+                    } else {
+                        (r_simd * simd_factor + l_simd).copy_to_slice(l);
+                    }
+                }
+
+                for (l, r) in lhs_chunks
+                    .into_remainder()
+                    .iter_mut()
+                    .zip(rhs_chunks.remainder())
+                {
+                    if cfg!(target_feature = "fma") {
+                        *l = r.mul_add(factor, *l);
+                    } else {
+                        *l += *r * factor;
+                    }
+                }
+            }
+
+            #[inline]
+            fn axpy_slice(lhs: &mut [Self], a: Self, rhs: &[Self], b: Self) {
+                let (simd_a, simd_b) = (Simd::<$t, $lanes>::splat(a), Simd::splat(b));
+                let mut lhs_chunks = lhs.chunks_exact_mut($lanes);
+                let mut rhs_chunks = rhs.chunks_exact($lanes);
+
+                for (l, r) in (&mut lhs_chunks).zip(&mut rhs_chunks) {
+                    let (l_simd, r_simd) = (Simd::from_slice(l), Simd::from_slice(r));
+                    if cfg!(target_feature = "fma") {
+                        r_simd.mul_add(simd_b, l_simd * simd_a).copy_to_slice(l);
+                    } else {
+                        (l_simd * simd_a + r_simd * simd_b).copy_to_slice(l);
+                    }
+                }
+
+                for (l, r) in lhs_chunks
+                    .into_remainder()
+                    .iter_mut()
+                    .zip(rhs_chunks.remainder())
+                {
+                    if cfg!(target_feature = "fma") {
+                        *l = r.mul_add(b, *l * a);
+                    } else {
+                        *l = *l * a + *r * b;
+                    }
+                }
+            }
+
+            #[inline]
+            fn scale_slice(values: &mut [Self], factor: Self) {
+                let simd_factor = Simd::<$t, $lanes>::splat(factor);
+                let mut chunks = values.chunks_exact_mut($lanes);
+
+                for chunk in &mut chunks {
+                    (Simd::from_slice(chunk) * simd_factor).copy_to_slice(chunk);
+                }
+
+                for x in chunks.into_remainder() {
+                    *x *= factor;
+                }
+            }
+
+            #[inline]
+            fn neg_slice(values: &mut [Self]) {
+                let mut chunks = values.chunks_exact_mut($lanes);
+
+                for chunk in &mut chunks {
+                    (-Simd::<$t, $lanes>::from_slice(chunk)).copy_to_slice(chunk);
+                }
+
+                for x in chunks.into_remainder() {
+                    *x = -*x;
+                }
+            }
+        }
+    };
+}
+
+impl_scalar!(f32, 8);
+impl_scalar!(f64, 4);
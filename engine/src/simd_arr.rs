@@ -6,23 +6,47 @@ use std::{
 pub mod dense_simd;
 pub mod hybrid_simd;
 mod sparse_simd;
+pub mod vec_simd;
+
+use crate::scalar::Scalar;
 
 pub trait SimdArr<const S: usize>:
-    Debug + Sized + Send + Sync + Index<usize, Output = f32> + IndexMut<usize, Output = f32> + Clone
+    Debug
+    + Sized
+    + Send
+    + Sync
+    + Index<usize, Output = <Self as SimdArr<S>>::Scalar>
+    + IndexMut<usize, Output = <Self as SimdArr<S>>::Scalar>
+    + Clone
 {
-    fn new_from_array(data: [f32; S]) -> Self;
+    type Scalar: Scalar;
+
+    fn new_from_array(data: [Self::Scalar; S]) -> Self;
 
-    fn new_from_value_and_pos(val: f32, pos: usize) -> Self;
+    fn new_from_value_and_pos(val: Self::Scalar, pos: usize) -> Self;
 
     fn zero() -> Self;
 
-    fn neg(&mut self); 
+    fn neg(&mut self);
 
-    fn to_array(&self) -> [f32; S];
+    fn to_array(&self) -> [Self::Scalar; S];
 
     fn acumulate(&mut self, rhs: &Self);
 
-    fn multiply(&mut self, rhs: f32);
+    fn multiply(&mut self, rhs: Self::Scalar);
+
+    // self += rhs * factor, implementations fuse it where they can
+    fn accumulate_scaled(&mut self, rhs: &Self, factor: Self::Scalar) {
+        let mut scaled = rhs.clone();
+        scaled.multiply(factor);
+        self.acumulate(&scaled);
+    }
+
+    // self = a * self + b * rhs
+    fn axpy(&mut self, a: Self::Scalar, rhs: &Self, b: Self::Scalar) {
+        self.multiply(a);
+        self.accumulate_scaled(rhs, b);
+    }
 
-    fn check_nan(&self);
-}
\ No newline at end of file
+    fn is_finite(&self) -> bool;
+}
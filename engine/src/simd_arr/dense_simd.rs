@@ -1,60 +1,145 @@
 use std::ops::{Index, IndexMut};
 
+use crate::scalar::Scalar;
+
 use super::SimdArr;
 
+// Every operation runs on std::simd vectors, see the slice kernels of Scalar. The values live on
+// the heap so that [Dual<P, DenseSimd<P>>; P] doesn't need a P² sized stack.
 #[derive(Clone, Debug)]
-pub struct DenseSimd<const S: usize>([f32; S]);
+pub struct DenseSimd<const S: usize, T: Scalar = f32>(Box<[T; S]>);
+
+impl<const S: usize, T: Scalar> SimdArr<S> for DenseSimd<S, T> {
+    type Scalar = T;
 
-impl<const S: usize> SimdArr<S> for DenseSimd<S> {
-    fn zero() -> DenseSimd<S> {
-        Self([0.; S])
+    // allocated as a vec, Box::new would build the array on the stack first
+    fn zero() -> DenseSimd<S, T> {
+        match vec![T::ZERO; S].into_boxed_slice().try_into() {
+            Ok(values) => Self(values),
+            Err(_) => unreachable!(),
+        }
     }
 
-    fn to_array(&self) -> [f32; S] {
-        self.0
+    fn to_array(&self) -> [T; S] {
+        *self.0
     }
 
-    fn new_from_value_and_pos(val: f32, pos: usize) -> Self {
-        let mut ret = Self([0.; S]);
+    fn new_from_value_and_pos(val: T, pos: usize) -> Self {
+        let mut ret = Self::zero();
         ret.0[pos] = val;
         ret
     }
 
+    #[inline]
     fn neg(&mut self) {
-        for i in 0..S {
-            self.0[i] *= -1.;
-        }
+        T::neg_slice(&mut *self.0);
     }
 
+    #[inline]
     fn acumulate(&mut self, rhs: &Self) {
-        for i in 0..S {
-            self.0[i] += rhs[i];
-        }
+        T::add_assign_slice(&mut *self.0, &*rhs.0);
     }
 
-    fn multiply(&mut self, rhs: f32) {
-        for x in &mut self.0 {
-            *x *= rhs;
-        }
+    #[inline]
+    fn multiply(&mut self, rhs: T) {
+        T::scale_slice(&mut *self.0, rhs);
     }
 
-    fn new_from_array(data: [f32; S]) -> DenseSimd<S> {
-        Self(data)
+    #[inline]
+    fn accumulate_scaled(&mut self, rhs: &Self, factor: T) {
+        T::mul_add_assign_slice(&mut *self.0, &*rhs.0, factor);
     }
 
-    fn check_nan(&self) {
-        // self.0.iter().for_each(|x| assert!(x.is_finite()));
+    #[inline]
+    fn axpy(&mut self, a: T, rhs: &Self, b: T) {
+        T::axpy_slice(&mut *self.0, a, &*rhs.0, b);
+    }
+
+    fn new_from_array(data: [T; S]) -> DenseSimd<S, T> {
+        let mut ret = Self::zero();
+        ret.0.copy_from_slice(&data);
+        ret
+    }
+
+    fn is_finite(&self) -> bool {
+        self.0.iter().all(|x| x.is_finite())
     }
 }
-impl<const S: usize> Index<usize> for DenseSimd<S> {
-    type Output = f32;
+impl<const S: usize, T: Scalar> Index<usize> for DenseSimd<S, T> {
+    type Output = T;
     fn index(&self, index: usize) -> &Self::Output {
         &self.0[index]
     }
 }
 
-impl<const S: usize> IndexMut<usize> for DenseSimd<S> {
+impl<const S: usize, T: Scalar> IndexMut<usize> for DenseSimd<S, T> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         &mut self.0[index]
     }
 }
+
+#[cfg(test)]
+mod dense_simd_tests {
+    use std::array;
+
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::simd_arr::SimdArr;
+
+    use super::DenseSimd;
+
+    // the sizes cover whole vectors, a remainder only and both
+    fn stress<const S: usize>(rng: &mut ChaCha8Rng) {
+        let a: [f32; S] = array::from_fn(|_| rng.gen_range(-10. ..10.));
+        let b: [f32; S] = array::from_fn(|_| rng.gen_range(-10. ..10.));
+        let factor = rng.gen_range(-10. ..10.);
+
+        let mut x = DenseSimd::new_from_array(a);
+        x.acumulate(&DenseSimd::new_from_array(b));
+        assert_eq!(x.to_array(), array::from_fn(|i| a[i] + b[i]));
+
+        let mut x = DenseSimd::new_from_array(a);
+        x.multiply(factor);
+        assert_eq!(x.to_array(), array::from_fn(|i| a[i] * factor));
+
+        let mut x = DenseSimd::new_from_array(a);
+        x.neg();
+        assert_eq!(x.to_array(), a.map(|x| -x));
+
+        // fused or not depending on the target, either way within a rounding of a + b * factor
+        let mut x = DenseSimd::new_from_array(a);
+        x.accumulate_scaled(&DenseSimd::new_from_array(b), factor);
+        for (i, x) in x.to_array().into_iter().enumerate() {
+            let expected = a[i] + b[i] * factor;
+            assert!((x - expected).abs() <= 1e-6 * (a[i].abs() + (b[i] * factor).abs()));
+        }
+
+        let scale = rng.gen_range(-10. ..10.);
+        let mut x = DenseSimd::new_from_array(a);
+        x.axpy(scale, &DenseSimd::new_from_array(b), factor);
+        for (i, x) in x.to_array().into_iter().enumerate() {
+            let expected = a[i] * scale + b[i] * factor;
+            assert!((x - expected).abs() <= 1e-6 * ((a[i] * scale).abs() + (b[i] * factor).abs()));
+        }
+
+        let wide: [f64; S] = a.map(f64::from);
+        let mut x = DenseSimd::new_from_array(wide);
+        x.accumulate_scaled(&DenseSimd::new_from_array(wide), 2.);
+        assert_eq!(x.to_array(), wide.map(|x| x * 3.));
+    }
+
+    #[test]
+    fn matches_scalar_loops() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+        for _ in 0..100 {
+            stress::<1>(&mut rng);
+            stress::<7>(&mut rng);
+            stress::<8>(&mut rng);
+            stress::<13>(&mut rng);
+            stress::<64>(&mut rng);
+            stress::<101>(&mut rng);
+        }
+    }
+}
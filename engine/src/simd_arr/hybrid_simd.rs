@@ -1,25 +1,29 @@
 use std::ops::{Index, IndexMut};
 
+use crate::scalar::Scalar;
+
 use super::{dense_simd::DenseSimd, sparse_simd::VecSparseSimd, SimdArr};
 
 #[derive(Clone, Debug)]
-pub enum HybridSimd<const SIZE: usize, const CRITIALITY: usize> {
-    Dense(Box<DenseSimd<SIZE>>),
-    Sparse(VecSparseSimd<CRITIALITY, SIZE>),
+pub enum HybridSimd<const SIZE: usize, const CRITIALITY: usize, T: Scalar = f32> {
+    Dense(DenseSimd<SIZE, T>),
+    Sparse(VecSparseSimd<CRITIALITY, SIZE, T>),
 }
 
-impl<const S: usize, const C: usize> SimdArr<S> for HybridSimd<S, C> {
-    fn new_from_array(arr: [f32; S]) -> Self {
+impl<const S: usize, const C: usize, T: Scalar> SimdArr<S> for HybridSimd<S, C, T> {
+    type Scalar = T;
+
+    fn new_from_array(arr: [T; S]) -> Self {
         match VecSparseSimd::new_from_array(&arr) {
-            None => HybridSimd::Dense(Box::new(DenseSimd::new_from_array(arr))),
+            None => HybridSimd::Dense(DenseSimd::new_from_array(arr)),
             Some(sparse) => HybridSimd::Sparse(sparse),
         }
     }
 
-    fn check_nan(&self) {
+    fn is_finite(&self) -> bool {
         match self {
-            HybridSimd::Dense(d) => d.check_nan(),
-            HybridSimd::Sparse(s) => s.check_nan(),
+            HybridSimd::Dense(d) => d.is_finite(),
+            HybridSimd::Sparse(s) => s.is_finite(),
         }
     }
 
@@ -27,7 +31,7 @@ impl<const S: usize, const C: usize> SimdArr<S> for HybridSimd<S, C> {
         Self::Sparse(VecSparseSimd::zero())
     }
 
-    fn to_array(&self) -> [f32; S] {
+    fn to_array(&self) -> [T; S] {
         match self {
             HybridSimd::Dense(d) => d.to_array(),
             HybridSimd::Sparse(s) => s.to_array(),
@@ -45,7 +49,7 @@ impl<const S: usize, const C: usize> SimdArr<S> for HybridSimd<S, C> {
         }
     }
 
-    fn new_from_value_and_pos(val: f32, pos: usize) -> Self {
+    fn new_from_value_and_pos(val: T, pos: usize) -> Self {
         HybridSimd::Sparse(VecSparseSimd::new_from_value_and_pos(val, pos))
     }
 
@@ -60,7 +64,7 @@ impl<const S: usize, const C: usize> SimdArr<S> for HybridSimd<S, C> {
                 let mut transformation = DenseSimd::new_from_array(res.to_array());
                 transformation.acumulate(b);
 
-                *res = HybridSimd::Dense(Box::new(transformation));
+                *res = HybridSimd::Dense(transformation);
             }
             (res @ HybridSimd::Sparse(_), HybridSimd::Sparse(b)) => {
                 let success = res.unwrap_sparse().acumulate(b);
@@ -68,22 +72,73 @@ impl<const S: usize, const C: usize> SimdArr<S> for HybridSimd<S, C> {
                     let mut transformation_self = DenseSimd::new_from_array(res.to_array());
                     let transformation_rhs = DenseSimd::new_from_array(b.to_array());
                     transformation_self.acumulate(&transformation_rhs);
-                    *res = HybridSimd::Dense(Box::new(transformation_self))
+                    *res = HybridSimd::Dense(transformation_self)
                 }
             }
         }
     }
 
-    fn multiply(&mut self, rhs: f32) {
+    fn multiply(&mut self, rhs: T) {
         match self {
             HybridSimd::Dense(d) => d.multiply(rhs),
             HybridSimd::Sparse(s) => s.multiply(rhs),
         }
     }
+
+    // same promotions as acumulate
+    fn accumulate_scaled(&mut self, rhs: &Self, factor: T) {
+        match (self, rhs) {
+            (HybridSimd::Dense(a), HybridSimd::Dense(b)) => a.accumulate_scaled(b, factor),
+            (HybridSimd::Dense(a), HybridSimd::Sparse(b)) => {
+                let transformation = DenseSimd::new_from_array(b.to_array());
+                a.accumulate_scaled(&transformation, factor);
+            }
+            (res @ HybridSimd::Sparse(_), HybridSimd::Dense(b)) => {
+                let mut transformation = DenseSimd::new_from_array(res.to_array());
+                transformation.accumulate_scaled(b, factor);
+
+                *res = HybridSimd::Dense(transformation);
+            }
+            (res @ HybridSimd::Sparse(_), HybridSimd::Sparse(b)) => {
+                let success = res.unwrap_sparse().accumulate_scaled(b, factor);
+                if success.is_err() {
+                    let mut transformation_self = DenseSimd::new_from_array(res.to_array());
+                    let transformation_rhs = DenseSimd::new_from_array(b.to_array());
+                    transformation_self.accumulate_scaled(&transformation_rhs, factor);
+                    *res = HybridSimd::Dense(transformation_self)
+                }
+            }
+        }
+    }
+
+    fn axpy(&mut self, a: T, rhs: &Self, b: T) {
+        match (self, rhs) {
+            (HybridSimd::Dense(x), HybridSimd::Dense(y)) => x.axpy(a, y, b),
+            (HybridSimd::Dense(x), HybridSimd::Sparse(y)) => {
+                let transformation = DenseSimd::new_from_array(y.to_array());
+                x.axpy(a, &transformation, b);
+            }
+            (res @ HybridSimd::Sparse(_), HybridSimd::Dense(y)) => {
+                let mut transformation = DenseSimd::new_from_array(res.to_array());
+                transformation.axpy(a, y, b);
+
+                *res = HybridSimd::Dense(transformation);
+            }
+            (res @ HybridSimd::Sparse(_), HybridSimd::Sparse(y)) => {
+                let success = res.unwrap_sparse().axpy(a, y, b);
+                if success.is_err() {
+                    let mut transformation_self = DenseSimd::new_from_array(res.to_array());
+                    let transformation_rhs = DenseSimd::new_from_array(y.to_array());
+                    transformation_self.axpy(a, &transformation_rhs, b);
+                    *res = HybridSimd::Dense(transformation_self)
+                }
+            }
+        }
+    }
 }
 
-impl<const S: usize, const C: usize> HybridSimd<S, C> {
-    fn unwrap_sparse<'a>(&'a mut self) -> &'a mut VecSparseSimd<C, S> {
+impl<const S: usize, const C: usize, T: Scalar> HybridSimd<S, C, T> {
+    fn unwrap_sparse(&mut self) -> &mut VecSparseSimd<C, S, T> {
         if let Self::Sparse(x) = self {
             x
         } else {
@@ -92,8 +147,8 @@ impl<const S: usize, const C: usize> HybridSimd<S, C> {
     }
 }
 
-impl<const S: usize, const C: usize> Index<usize> for HybridSimd<S, C> {
-    type Output = f32;
+impl<const S: usize, const C: usize, T: Scalar> Index<usize> for HybridSimd<S, C, T> {
+    type Output = T;
 
     fn index(&self, index: usize) -> &Self::Output {
         match self {
@@ -103,7 +158,7 @@ impl<const S: usize, const C: usize> Index<usize> for HybridSimd<S, C> {
     }
 }
 
-impl<const S: usize, const C: usize> IndexMut<usize> for HybridSimd<S, C> {
+impl<const S: usize, const C: usize, T: Scalar> IndexMut<usize> for HybridSimd<S, C, T> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         match self {
             HybridSimd::Dense(d) => &mut d[index],
@@ -199,6 +254,36 @@ mod hybrid_simd_tests {
         assert_eq!(test.to_array(), res)
     }
 
+    // small capacity so that some merges overflow into dense
+    #[test]
+    fn fused_matches_unfused() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+        for _ in 0..1000 {
+            let cero_ratio: f32 = rng.gen();
+            let a: [f32; 32] = from_fn(|_| (rng.gen::<f32>() - cero_ratio).max(0.));
+            let b: [f32; 32] = from_fn(|_| (rng.gen::<f32>() - cero_ratio).max(0.));
+            let (scale, factor) = (rng.gen_range(-10. ..10.), rng.gen_range(-10. ..10.));
+
+            let close = |x: [f32; 32], expected: [f32; 32]| {
+                x.iter()
+                    .zip(expected)
+                    .all(|(x, expected)| (x - expected).abs() <= 1e-5 * (1. + expected.abs()))
+            };
+
+            let mut x = HybridSimd::<32, 8>::new_from_array(a);
+            x.accumulate_scaled(&HybridSimd::new_from_array(b), factor);
+            assert!(close(x.to_array(), from_fn(|i| a[i] + b[i] * factor)));
+
+            let mut x = HybridSimd::<32, 8>::new_from_array(a);
+            x.axpy(scale, &HybridSimd::new_from_array(b), factor);
+            assert!(close(
+                x.to_array(),
+                from_fn(|i| a[i] * scale + b[i] * factor)
+            ));
+        }
+    }
+
     #[test]
     fn stress_test_scalar() {
         let mut rng = ChaCha8Rng::seed_from_u64(2);
@@ -246,9 +331,7 @@ mod hybrid_simd_tests {
                 assert_eq!(x.to_array()[i], a[i]);
             }
 
-            for i in 0..4 {
-                assert_eq!(x.to_array()[i], a[i]);
-            }
+            assert_eq!(x.to_array(), a);
         }
     }
 
@@ -268,9 +351,7 @@ mod hybrid_simd_tests {
                 assert_eq!(y.to_array()[i], a[i]);
             }
 
-            for i in 0..4 {
-                assert_eq!(y.to_array()[i], a[i]);
-            }
+            assert_eq!(y.to_array(), a);
         }
     }
 
@@ -1,22 +1,26 @@
 use std::ops::{Index, IndexMut};
 
+use crate::scalar::Scalar;
+
 #[derive(Clone, Debug, PartialEq)]
-pub struct VecSparseSimd<const CAPACITY: usize, const VIRTUALSIZE: usize> {
+pub struct VecSparseSimd<const CAPACITY: usize, const VIRTUALSIZE: usize, T: Scalar = f32> {
     data_index: Vec<usize>,
-    data: Vec<f32>,
+    data: Vec<T>,
 }
 
-impl<const CAPACITY: usize, const VIRTUALSIZE: usize> VecSparseSimd<CAPACITY, VIRTUALSIZE> {
+impl<const CAPACITY: usize, const VIRTUALSIZE: usize, T: Scalar>
+    VecSparseSimd<CAPACITY, VIRTUALSIZE, T>
+{
     pub fn non_zero_count(&self) -> usize {
         self.data.len()
     }
 }
 
-impl<const CAPACITY: usize, const S: usize> VecSparseSimd<CAPACITY, S> {
-    pub fn new_from_array(arr: &[f32; S]) -> Option<Self> {
+impl<const CAPACITY: usize, const S: usize, T: Scalar> VecSparseSimd<CAPACITY, S, T> {
+    pub fn new_from_array(arr: &[T; S]) -> Option<Self> {
         let mut ret = Self::zero();
         for (i, &elm) in arr.iter().enumerate() {
-            if elm != 0. {
+            if elm != T::ZERO {
                 if ret.data.len() == CAPACITY {
                     return None;
                 }
@@ -28,18 +32,18 @@ impl<const CAPACITY: usize, const S: usize> VecSparseSimd<CAPACITY, S> {
         Some(ret)
     }
 
-    pub fn check_nan(&self) {
-        // self.data.iter().for_each(|x| assert!(x.is_finite()));
+    pub fn is_finite(&self) -> bool {
+        self.data.iter().all(|x| x.is_finite())
     }
 
-    pub fn zero() -> VecSparseSimd<CAPACITY, S> {
+    pub fn zero() -> Self {
         Self {
             data_index: Vec::new(),
             data: Vec::new(),
         }
     }
 
-    pub fn zero_with_capacity(capacity: usize) -> VecSparseSimd<CAPACITY, S> {
+    pub fn zero_with_capacity(capacity: usize) -> Self {
         // println!("creating sparse simd with {:?} as capacity", capacity);
         Self {
             data_index: Vec::with_capacity(capacity),
@@ -47,8 +51,8 @@ impl<const CAPACITY: usize, const S: usize> VecSparseSimd<CAPACITY, S> {
         }
     }
 
-    pub fn to_array(&self) -> [f32; S] {
-        let mut ret = [0.; S];
+    pub fn to_array(&self) -> [T; S] {
+        let mut ret = [T::ZERO; S];
 
         for i in 0..self.data.len() {
             ret[self.data_index[i]] = self.data[i];
@@ -57,7 +61,7 @@ impl<const CAPACITY: usize, const S: usize> VecSparseSimd<CAPACITY, S> {
         ret
     }
 
-    pub fn new_from_value_and_pos(val: f32, pos: usize) -> Self {
+    pub fn new_from_value_and_pos(val: T, pos: usize) -> Self {
         let mut ret = Self::zero();
         ret.data_index.push(pos);
         ret.data.push(val);
@@ -66,15 +70,27 @@ impl<const CAPACITY: usize, const S: usize> VecSparseSimd<CAPACITY, S> {
 
     pub fn neg(&mut self) {
         for i in 0..self.data.len() {
-            self.data[i] *= -1.;
+            self.data[i] = -self.data[i];
         }
     }
 
     pub fn acumulate(&mut self, rhs: &Self) -> Result<(), ()> {
-        if rhs.data.len() == 0 {
+        self.axpy(T::ONE, rhs, T::ONE)
+    }
+
+    pub fn accumulate_scaled(&mut self, rhs: &Self, factor: T) -> Result<(), ()> {
+        self.axpy(T::ONE, rhs, factor)
+    }
+
+    // self = a * self + b * rhs in a single merge of both indices. On Err (the result doesn't
+    // fit in CAPACITY) self is left untouched.
+    pub fn axpy(&mut self, a: T, rhs: &Self, b: T) -> Result<(), ()> {
+        if rhs.data.is_empty() {
+            self.multiply(a);
             Ok(())
-        } else if self.data.len() == 0 {
+        } else if self.data.is_empty() {
             *self = rhs.clone();
+            self.multiply(b);
             Ok(())
         } else {
             let mut ret = Self::zero_with_capacity(self.data.len() + rhs.data.len());
@@ -90,7 +106,7 @@ impl<const CAPACITY: usize, const S: usize> VecSparseSimd<CAPACITY, S> {
                             return Err(());
                         }
                         ret.data_index.push(*rhs_idx);
-                        ret.data.push(*rhs_val);
+                        ret.data.push(*rhs_val * b);
                         rhs_iter.next();
                     } else {
                         break;
@@ -103,30 +119,30 @@ impl<const CAPACITY: usize, const S: usize> VecSparseSimd<CAPACITY, S> {
                             return Err(());
                         }
                         ret.data_index.push(*rhs_idx);
-                        ret.data.push(*self_val + rhs_val);
+                        ret.data.push(*self_val * a + *rhs_val * b);
                         rhs_iter.next();
                     } else {
                         if ret.data.len() == CAPACITY {
                             return Err(());
                         }
                         ret.data_index.push(*self_idx);
-                        ret.data.push(*self_val);
+                        ret.data.push(*self_val * a);
                     }
                 } else {
                     if ret.data.len() == CAPACITY {
                         return Err(());
                     }
                     ret.data_index.push(*self_idx);
-                    ret.data.push(*self_val);
+                    ret.data.push(*self_val * a);
                 }
             }
 
-            while let Some((rhs_idx, rhs_val)) = rhs_iter.next() {
+            for (rhs_idx, rhs_val) in rhs_iter {
                 if ret.data.len() == CAPACITY {
                     return Err(());
                 }
                 ret.data_index.push(*rhs_idx);
-                ret.data.push(*rhs_val);
+                ret.data.push(*rhs_val * b);
             }
 
             self.data = ret.data;
@@ -136,29 +152,29 @@ impl<const CAPACITY: usize, const S: usize> VecSparseSimd<CAPACITY, S> {
         }
     }
 
-    pub fn multiply(&mut self, rhs: f32) {
+    pub fn multiply(&mut self, rhs: T) {
         for i in 0..self.data.len() {
             self.data[i] *= rhs;
         }
     }
 }
 
-impl<const CAPACITY: usize, const VIRTUALSIZE: usize> Index<usize>
-    for VecSparseSimd<CAPACITY, VIRTUALSIZE>
+impl<const CAPACITY: usize, const VIRTUALSIZE: usize, T: Scalar> Index<usize>
+    for VecSparseSimd<CAPACITY, VIRTUALSIZE, T>
 {
-    type Output = f32;
+    type Output = T;
     fn index(&self, index: usize) -> &Self::Output {
         let dereference_index = self.data_index.partition_point(|i| *i < index);
         if dereference_index < self.data.len() && self.data_index[dereference_index] == index {
             &self.data[dereference_index]
         } else {
-            &0.
+            T::zero_ref()
         }
     }
 }
 
-impl<const CAPACITY: usize, const VIRTUALSIZE: usize> IndexMut<usize>
-    for VecSparseSimd<CAPACITY, VIRTUALSIZE>
+impl<const CAPACITY: usize, const VIRTUALSIZE: usize, T: Scalar> IndexMut<usize>
+    for VecSparseSimd<CAPACITY, VIRTUALSIZE, T>
 {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         let dereference_index = self.data_index.partition_point(|i| *i < index);
@@ -170,7 +186,7 @@ impl<const CAPACITY: usize, const VIRTUALSIZE: usize> IndexMut<usize>
             &mut self.data[dereference_index]
         } else {
             self.data_index.push(index);
-            self.data.push(0.);
+            self.data.push(T::ZERO);
 
             self.data_index[dereference_index..=prev_size].rotate_right(1);
             self.data[dereference_index..=prev_size].rotate_right(1);
@@ -255,6 +271,40 @@ mod vec_sparse_simd_tests {
         }
     }
 
+    fn test_axpy<const N: usize>(a: [f32; N], scale: f32, b: [f32; N], factor: f32) {
+        let res: [f32; N] = from_fn(|i| a[i] * scale + b[i] * factor);
+
+        let mut x = VecSparseSimd::<N, N>::new_from_array(&a).unwrap();
+        let y = VecSparseSimd::<N, N>::new_from_array(&b).unwrap();
+
+        x.axpy(scale, &y, factor).unwrap();
+
+        assert_eq!(x.to_array(), res)
+    }
+
+    #[test]
+    fn axpy_stress_test() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+        for _ in 0..1000 {
+            let cero_ratio: f32 = rng.gen();
+            let a: [f32; 32] = rand::random::<[f32; 32]>().map(|x| (x - cero_ratio).max(0.));
+            let b: [f32; 32] = rand::random::<[f32; 32]>().map(|x| (x - cero_ratio).max(0.));
+
+            test_axpy(a, rng.gen(), b, rng.gen());
+            test_axpy(a, 1., b, rng.gen());
+        }
+    }
+
+    #[test]
+    fn overflow_leaves_self_untouched() {
+        let mut x = VecSparseSimd::<2, 4>::new_from_array(&[1., 0., 2., 0.]).unwrap();
+        let y = VecSparseSimd::<2, 4>::new_from_array(&[0., 3., 0., 0.]).unwrap();
+
+        assert!(x.axpy(2., &y, 3.).is_err());
+        assert_eq!(x.to_array(), [1., 0., 2., 0.]);
+    }
+
     #[test]
     fn consistency_stress_test() {
         let mut rng = ChaCha8Rng::seed_from_u64(2);
@@ -337,9 +387,7 @@ mod vec_sparse_simd_tests {
                 assert_eq!(x.to_array()[i], a[i]);
             }
 
-            for i in 0..4 {
-                assert_eq!(x.to_array()[i], a[i]);
-            }
+            assert_eq!(x.to_array(), a);
         }
     }
 
@@ -359,9 +407,7 @@ mod vec_sparse_simd_tests {
                 assert_eq!(y.to_array()[i], a[i]);
             }
 
-            for i in 0..4 {
-                assert_eq!(y.to_array()[i], a[i]);
-            }
+            assert_eq!(y.to_array(), a);
         }
     }
 
@@ -0,0 +1,121 @@
+use std::ops::{Index, IndexMut};
+
+// Runtime sized counterpart of DenseSimd. The length is not part of the type, the vector only
+// grows as far as the highest position written, every position past its end reads as 0.
+#[derive(Clone, Debug, Default)]
+pub struct VecSimd(Vec<f32>);
+
+impl VecSimd {
+    pub fn zero() -> Self {
+        Self(vec![])
+    }
+
+    pub fn new_from_slice(data: &[f32]) -> Self {
+        Self(data.to_vec())
+    }
+
+    pub fn new_from_value_and_pos(val: f32, pos: usize) -> Self {
+        let mut ret = vec![0.; pos + 1];
+        ret[pos] = val;
+        Self(ret)
+    }
+
+    pub fn neg(&mut self) {
+        for x in &mut self.0 {
+            *x *= -1.;
+        }
+    }
+
+    pub fn to_vec(&self, size: usize) -> Vec<f32> {
+        let mut ret = vec![0.; size];
+        self.write_to(&mut ret);
+        ret
+    }
+
+    pub fn write_to(&self, out: &mut [f32]) {
+        out[..self.0.len()].copy_from_slice(&self.0);
+    }
+
+    pub fn acumulate(&mut self, rhs: &Self) {
+        if self.0.len() < rhs.0.len() {
+            self.0.resize(rhs.0.len(), 0.);
+        }
+
+        for (x, y) in self.0.iter_mut().zip(&rhs.0) {
+            *x += y;
+        }
+    }
+
+    pub fn multiply(&mut self, rhs: f32) {
+        for x in &mut self.0 {
+            *x *= rhs;
+        }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.0.iter().all(|x| x.is_finite())
+    }
+}
+
+impl Index<usize> for VecSimd {
+    type Output = f32;
+    fn index(&self, index: usize) -> &Self::Output {
+        self.0.get(index).unwrap_or(&0.)
+    }
+}
+
+impl IndexMut<usize> for VecSimd {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        if self.0.len() <= index {
+            self.0.resize(index + 1, 0.);
+        }
+        &mut self.0[index]
+    }
+}
+
+#[cfg(test)]
+mod vec_simd_tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::simd_arr::{dense_simd::DenseSimd, SimdArr};
+
+    use super::VecSimd;
+
+    #[test]
+    fn grows_on_demand() {
+        let mut x = VecSimd::zero();
+        assert_eq!(x[3], 0.);
+
+        x.acumulate(&VecSimd::new_from_value_and_pos(2., 3));
+        x[5] = 1.;
+
+        assert_eq!(x.to_vec(8), vec![0., 0., 0., 2., 0., 1., 0., 0.]);
+    }
+
+    #[test]
+    fn matches_dense() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+        for _ in 0..100 {
+            let a: [f32; 16] = std::array::from_fn(|_| rng.gen());
+            let b: [f32; 16] = std::array::from_fn(|_| rng.gen());
+            let pos = rng.gen_range(0..16);
+            let factor = rng.gen();
+
+            let mut dense = DenseSimd::new_from_array(a);
+            dense.acumulate(&DenseSimd::new_from_array(b));
+            dense.acumulate(&DenseSimd::new_from_value_and_pos(factor, pos));
+            dense.multiply(factor);
+            dense.neg();
+
+            let mut vec = VecSimd::new_from_slice(&a);
+            vec.acumulate(&VecSimd::new_from_slice(&b));
+            vec.acumulate(&VecSimd::new_from_value_and_pos(factor, pos));
+            vec.multiply(factor);
+            vec.neg();
+
+            assert_eq!(vec.to_vec(16), dense.to_array());
+        }
+    }
+}
@@ -1,140 +1,606 @@
+pub mod checkpoint;
+pub mod dataset;
+pub mod dyn_trainer;
+pub mod early_stopping;
+pub mod error;
+pub mod gradient;
+pub mod initializer;
+pub mod loss;
+pub mod optimizer;
+pub mod regularization;
+
 use std::array;
-use std::fs::OpenOptions;
-use std::io::{self, BufRead, Write};
-use std::ops::{Add, Div, Sub};
+use std::ops::{Add, Div, Mul, Sub};
 use std::time::Instant;
 
+use crate::differentiable::Differentiable;
 use crate::dual::extended_arithmetic::ExtendedArithmetic;
 use crate::dual::Dual;
+use crate::hyper_dual::HyperDual;
+use crate::reverse::Reverse;
+use crate::scalar::Scalar;
 use crate::simd_arr::dense_simd::DenseSimd;
 use crate::simd_arr::hybrid_simd::HybridSimd;
 use crate::simd_arr::SimdArr;
-use indicatif::ParallelProgressIterator;
+use crate::trainer::checkpoint::Checkpoint;
+use crate::trainer::dataset::{minibatch, Dataset};
+use crate::trainer::early_stopping::{EarlyStopping, EpochOutcome};
+use crate::trainer::error::{non_finite_indices, CheckpointError, TrainError};
+use crate::trainer::gradient::{GradientClipping, StepReport};
+use crate::trainer::initializer::Initializer;
+use crate::trainer::loss::Loss;
+use crate::trainer::optimizer::asintotic_search::AsintoticSearch;
+use crate::trainer::optimizer::Optimizer;
+use crate::trainer::regularization::Regularization;
+use indicatif::ProgressBar;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
 use std::fmt::Debug;
+use std::sync::Arc;
 
+// T is the scalar of the model, f64 models take f64 datasets. The dataset cost is the mean of the
+// data point costs weighted by `weight`. Data points don't depend on the model, any model with
+// the same inputs and outputs trains on them.
 #[derive(Debug, Clone, Copy)]
-pub struct DataPoint<const P: usize, const I: usize, const O: usize> {
-    pub input: [f32; I],
-    pub output: [f32; O],
+pub struct DataPoint<const I: usize, const O: usize, T = f32> {
+    pub input: [T; I],
+    pub output: [T; O],
+    pub weight: T,
+}
+
+impl<const I: usize, const O: usize, T: Scalar> DataPoint<I, O, T> {
+    pub fn new(input: [T; I], output: [T; O]) -> Self {
+        Self {
+            input,
+            output,
+            weight: T::ONE,
+        }
+    }
+
+    // weights have to be finite and not negative, the train steps refuse datasets with other ones
+    pub fn with_weight(mut self, weight: T) -> Self {
+        self.weight = weight;
+        self
+    }
 }
 
 pub struct CriticalityCue<const CRITICALITY: usize>();
 
-fn datapoint_cost<
-    const P: usize,
-    const I: usize,
-    const O: usize,
-    N: ExtendedArithmetic + Clone + Sub<f32, Output = N> + Add<N, Output = N> + Debug + From<f32>,
->(
-    goal: &DataPoint<P, I, O>,
-    prediction: [N; O],
-) -> N {
-    let mut ret = N::from(0.);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    // the optimizer found a step and the params were updated
+    Stepped,
+    // no step was found, the params are unchanged
+    Stalled,
+}
 
-    for (pred_val, goal_val) in prediction.clone().into_iter().zip(goal.output.into_iter()) {
-        let cost = pred_val.clone() - goal_val;
-        // println!("    scalar cost for {pred_val:?} and {goal_val:?} is {cost:?}");
-        // println!("{ret:?} + {cost:?}");
+// why dataset_cost has no cost. The non finite parameters of a NonFinite cost depend on its
+// number type, the caller fills them in when turning it into a TrainError
+enum CostError<N> {
+    NonFinite { data_point: usize, cost: N },
+    Train(TrainError),
+}
 
-        ret = ret + cost.abs();
+impl<N> CostError<N> {
+    fn into_train_error(self, parameters: impl FnOnce(&N) -> Vec<usize>) -> TrainError {
+        match self {
+            CostError::NonFinite { data_point, cost } => TrainError::NonFiniteCost {
+                data_point,
+                parameters: parameters(&cost),
+            },
+            CostError::Train(err) => err,
+        }
+    }
 
-        // ret = ret + cost.pow2();
+    // for the costs that are compared (line search, validation), a non finite one never wins
+    fn nan_if_non_finite<T: From<f32>>(self) -> Result<T, TrainError> {
+        match self {
+            CostError::NonFinite { .. } => Ok(T::from(f32::NAN)),
+            CostError::Train(err) => Err(err),
+        }
     }
-    ret
 }
 
+// data points a thread sums on its own and chunks evaluated between the in order sums
+const COST_CHUNK: usize = 8;
+const COST_BATCH: usize = 256;
+
+// weighted mean of the data point costs, errors when the weights add up to 0 (ie: an empty
+// dataset) as there is nothing to average
 fn dataset_cost<
-    'a,
-    'b,
     const PROGRESS: bool,
     const DEBUG: bool,
     const PARALELIZE: bool,
-    const P: usize,
     const I: usize,
     const O: usize,
+    T: Scalar,
     ExtraData: Sync + Clone,
+    Params: Sync + ?Sized,
     N: ExtendedArithmetic
         + Clone
-        + Sub<f32, Output = N>
-        + Add<f32, Output = N>
+        + Sub<T, Output = N>
+        + Add<T, Output = N>
         + Debug
         + From<f32>
         + Add<N, Output = N>
-        + Div<f32, Output = N>
+        + Mul<N, Output = N>
+        + Mul<T, Output = N>
+        + Div<T, Output = N>
+        + PartialOrd<T>
         + Send
         + Sync,
-    F: Fn(&[N; P], &[f32; I], &ExtraData) -> [N; O] + Sync,
-    D: IntoIterator<Item = &'b DataPoint<P, I, O>>
-        + IntoParallelIterator<Item = &'a DataPoint<P, I, O>>,
+    F: Fn(&Params, &[T; I], &ExtraData) -> [N; O] + Sync,
+    D: Dataset<I, O, T> + ?Sized,
 >(
-    dataset: D,
-    dataset_len: usize,
-    params: &[N; P],
+    dataset: &D,
+    params: &Params,
     model: F,
     extra: &ExtraData,
-) -> N {
-    let mut accumulator = N::from(0.);
-    let cost_list = if PARALELIZE {
-        if PROGRESS {
-            dataset
-                .into_par_iter()
-                .progress_count(dataset_len as u64)
-                .map(|data_point| {
-                    let prediction = (model)(&params, &data_point.input, &extra);
-
-                    if DEBUG {
-                        println!("goal {:?} predition {:?}", data_point.output, prediction);
-                    }
-
-                    datapoint_cost(&data_point, prediction)
-                })
-                .collect::<Vec<_>>()
-        } else {
-            dataset
-                .into_par_iter()
-                .map(|data_point| {
-                    let prediction = (model)(&params, &data_point.input, &extra);
-                    if DEBUG {
-                        println!("goal {:?} predition {:?}", data_point.output, prediction);
-                    }
-                    datapoint_cost(&data_point, prediction)
-                })
-                .collect::<Vec<_>>()
-        }
-    } else {
-        dataset
-            .into_iter()
-            .map(|data_point| {
-                let prediction = (model)(&params, &data_point.input, &extra);
-                if DEBUG {
-                    println!("goal {:?} predition {:?}", data_point.output, prediction);
-                }
-                datapoint_cost(&data_point, prediction)
-            })
-            .collect::<Vec<_>>()
+    loss: &Loss,
+    output_weights: Option<&[T; O]>,
+    thread_pool: Option<&ThreadPool>,
+) -> Result<N, CostError<N>> {
+    let progress = PROGRESS.then(|| ProgressBar::new(dataset.len() as u64));
+    // the weighted cost and the weight of a chunk of data points, summed in order
+    let chunk_cost = |chunk: usize| {
+        let data_points = chunk * COST_CHUNK..dataset.len().min((chunk + 1) * COST_CHUNK);
+        let mut chunk_sum = N::from(0.);
+        let mut chunk_weight = T::ZERO;
+        for i in data_points.clone() {
+            let data_point = dataset
+                .get(i)
+                .map_err(|err| CostError::Train(TrainError::dataset(i, err)))?;
+            let weight = data_point.weight;
+            if weight < T::ZERO || !weight.is_finite() {
+                return Err(CostError::Train(TrainError::InvalidWeight {
+                    data_point: i,
+                }));
+            }
+
+            let prediction = (model)(params, &data_point.input, extra);
+            if DEBUG {
+                println!("goal {:?} predition {:?}", data_point.output, prediction);
+            }
+            let cost = loss.weighted_cost(prediction, &data_point.output, output_weights);
+            if !cost.is_finite() {
+                return Err(CostError::NonFinite {
+                    data_point: i,
+                    cost,
+                });
+            }
+            // unweighted points skip the product, it costs as much as the sum on duals
+            chunk_sum = if weight == T::ONE {
+                chunk_sum + cost
+            } else {
+                chunk_sum + cost * weight
+            };
+            chunk_weight += weight;
+        }
+        if let Some(progress) = &progress {
+            progress.inc(data_points.len() as u64);
+        }
+        Ok((chunk_sum, chunk_weight))
+    };
+
+    // the chunks of a batch are evaluated in parallel and added in order, so only a batch of costs
+    // is held at once and the result doesn't depend on the thread count or the scheduling
+    let chunk_count = dataset.len().div_ceil(COST_CHUNK);
+    let sum_batches = || {
+        let mut accumulator = N::from(0.);
+        let mut weight_sum = T::ZERO;
+        for batch in (0..chunk_count).step_by(COST_BATCH) {
+            let chunks = batch..chunk_count.min(batch + COST_BATCH);
+            let batch_costs: Vec<_> = if PARALELIZE {
+                chunks.into_par_iter().map(chunk_cost).collect()
+            } else {
+                chunks.map(chunk_cost).collect()
+            };
+            for chunk in batch_costs {
+                let (chunk_sum, chunk_weight) = chunk?;
+                accumulator = accumulator + chunk_sum;
+                weight_sum += chunk_weight;
+            }
+        }
+        Ok((accumulator, weight_sum))
+    };
+    let (mut accumulator, weight_sum) = match thread_pool {
+        Some(thread_pool) if PARALELIZE => thread_pool.install(sum_batches),
+        _ => sum_batches(),
+    }?;
+
+    if weight_sum <= T::ZERO {
+        return Err(CostError::Train(TrainError::NoWeight));
+    }
+    accumulator = accumulator / weight_sum;
+
+    Ok(accumulator)
+}
+
+fn get_gradient<N: Differentiable>(cost: &N, param_count: usize) -> Vec<N::Scalar> {
+    let mut gradient = vec![N::Scalar::ZERO; param_count];
+    cost.write_gradient(&mut gradient);
+    gradient
+}
+
+// Shared by every trainer once the gradient of the dir cost is known. `translate` maps the
+// optimizer displacement onto new params and `full_cost` evaluates them on the full dataset, the
+// regularization is added on top of both costs. The report cost is the one to keep as the last one.
+#[allow(clippy::too_many_arguments)]
+fn optimizer_step<
+    const VERBOSE: bool,
+    N: Differentiable,
+    Opt: Optimizer,
+    T: Fn(&[N::Scalar], &[N::Scalar]) -> Vec<N::Scalar>,
+    C: FnMut(&[N::Scalar]) -> Result<N::Scalar, TrainError>,
+>(
+    optimizer: &mut Opt,
+    params: &mut [N],
+    dir_cost: N::Scalar,
+    mut gradient: Vec<N::Scalar>,
+    clipping: &GradientClipping,
+    regularization: &Regularization,
+    translate: T,
+    mut full_cost: C,
+    t0: Instant,
+) -> Result<StepReport<N::Scalar>, TrainError> {
+    let og_parameters: Vec<N::Scalar> = params.iter().map(|p| p.get_real()).collect();
+
+    let mut report = StepReport::new(&gradient);
+    let dir_cost = dir_cost + regularization.penalty(&og_parameters);
+    regularization.add_gradient(&og_parameters, &mut gradient);
+    report.clipped = clipping.clip(&mut gradient);
+
+    let translate = |displacement: &[N::Scalar]| {
+        let mut new_params = translate(&og_parameters, displacement);
+        regularization.decay(&mut new_params);
+        new_params
+    };
+
+    // the optimizer only takes costs, the first error is kept and the step dropped
+    let mut full_cost_error = None;
+    let step = optimizer.step(&og_parameters, &gradient, |displacement| {
+        let new_params = translate(displacement);
+        match full_cost(&new_params) {
+            Ok(cost) => cost + regularization.penalty(&new_params),
+            Err(err) => {
+                full_cost_error.get_or_insert(err);
+                N::Scalar::from(f32::NAN)
+            }
+        }
+    });
+    if let Some(err) = full_cost_error {
+        return Err(err);
+    }
+
+    let Some(step) = step else {
+        report.cost = dir_cost;
+        report.duration = t0.elapsed();
+        return Ok(report);
     };
 
-    for cost in cost_list {
-        accumulator = accumulator + cost;
+    let new_params = translate(&step.displacement);
+
+    let non_finite = non_finite_indices(&new_params);
+    if !non_finite.is_empty() {
+        return Err(TrainError::NonFiniteParameters {
+            parameters: non_finite,
+        });
+    }
+
+    for (param, new_param) in params.iter_mut().zip(new_params) {
+        param.set_real(new_param);
+    }
+
+    report.outcome = StepOutcome::Stepped;
+    report.cost = step.cost.unwrap_or(dir_cost);
+    report.duration = t0.elapsed();
+
+    if VERBOSE {
+        println!(
+            "gradient length: {:?} - zero gradients: {} - dir cost: {:?} - new cost: {:?} - time {}",
+            report.gradient_norm,
+            report.zero_gradients,
+            dir_cost,
+            step.cost,
+            report.duration.as_secs_f32()
+        );
     }
 
-    accumulator = accumulator / dataset_len as f32;
+    Ok(report)
+}
+
+// seed of the trainers built without with_seed
+const DEFAULT_SEED: u64 = 2;
+
+// What Trainer and DynTrainer share: the training setup and progress, everything but the model
+// and its params. The trainers hand their params over as slices and their models as closures on
+// those slices, so the train steps are written once for both.
+#[derive(Clone)]
+struct TrainerBase<T, const O: usize, ExtraData, Opt> {
+    seed: u64,
+    initializer: Initializer<T>,
+    // drives the params initialization and shake
+    rng: ChaCha8Rng,
+    extra_data: ExtraData,
+    last_cost: Option<T>,
+    last_report: Option<StepReport<T>>,
+    optimizer: Opt,
+    loss: Loss,
+    output_weights: Option<[T; O]>,
+    model_id: String,
+    step_count: u64,
+    early_stopping: EarlyStopping,
+    thread_pool: Option<Arc<ThreadPool>>,
+    gradient_clipping: GradientClipping,
+    regularization: Regularization,
+}
 
-    accumulator
+impl<T: Scalar, const O: usize, ExtraData: Sync + Clone>
+    TrainerBase<T, O, ExtraData, AsintoticSearch>
+{
+    fn new(extra_data: ExtraData) -> Self {
+        Self {
+            seed: DEFAULT_SEED,
+            initializer: Initializer::default(),
+            rng: ChaCha8Rng::seed_from_u64(DEFAULT_SEED),
+            extra_data,
+            last_cost: None,
+            last_report: None,
+            optimizer: AsintoticSearch::default(),
+            loss: Loss::default(),
+            output_weights: None,
+            model_id: String::new(),
+            step_count: 0,
+            early_stopping: EarlyStopping::default(),
+            thread_pool: None,
+            gradient_clipping: GradientClipping::default(),
+            regularization: Regularization::default(),
+        }
+    }
 }
 
-pub fn default_param_translator<const P: usize>(params: &[f32; P], vector: &[f32; P]) -> [f32; P] {
+impl<T: Scalar, const O: usize, ExtraData: Sync + Clone, Opt: Optimizer>
+    TrainerBase<T, O, ExtraData, Opt>
+{
+    fn with_optimizer<NewOpt: Optimizer>(
+        self,
+        optimizer: NewOpt,
+    ) -> TrainerBase<T, O, ExtraData, NewOpt> {
+        TrainerBase {
+            seed: self.seed,
+            initializer: self.initializer,
+            rng: self.rng,
+            extra_data: self.extra_data,
+            last_cost: self.last_cost,
+            last_report: self.last_report,
+            optimizer,
+            loss: self.loss,
+            output_weights: self.output_weights,
+            model_id: self.model_id,
+            step_count: self.step_count,
+            early_stopping: self.early_stopping,
+            thread_pool: self.thread_pool,
+            gradient_clipping: self.gradient_clipping,
+            regularization: self.regularization,
+        }
+    }
+
+    fn random_param<N: Differentiable<Scalar = T>>(&mut self, i: usize) -> N {
+        N::new_param(self.initializer.sample(i, &mut self.rng), i)
+    }
+
+    // draws the params again from the seed, two trainers with the same seed and the same calls
+    // end up with bit-identical params
+    fn initialize_params<N: Differentiable<Scalar = T>>(&mut self, params: &mut [N]) {
+        self.rng = ChaCha8Rng::seed_from_u64(self.seed);
+        for (i, param) in params.iter_mut().enumerate() {
+            *param = self.random_param(i);
+        }
+    }
+
+    fn shake<N: Differentiable<Scalar = T>>(&mut self, params: &mut [N], factor: f32) {
+        for param in params {
+            param.set_real(param.get_real() + T::from((self.rng.gen::<f32>() - 0.5) * factor));
+        }
+    }
+
+    // the optimizer state is kept, last_cost is dropped as it belongs to the old params
+    fn set_params<N: Differentiable<Scalar = T>>(&mut self, params: &mut [N], values: &[T]) {
+        assert_eq!(values.len(), params.len(), "wrong param count");
+
+        for (param, value) in params.iter_mut().zip(values) {
+            param.set_real(*value);
+        }
+        self.last_cost = None;
+    }
+
+    fn checkpoint<N: Differentiable<Scalar = T>>(&self, params: &[N]) -> Checkpoint {
+        Checkpoint {
+            model_id: self.model_id.clone(),
+            params: params.iter().map(|p| p.get_real().into()).collect(),
+            optimizer: Opt::NAME.to_string(),
+            optimizer_state: self.optimizer.state(),
+            last_cost: self.last_cost.map(Into::into),
+            step_count: self.step_count,
+        }
+    }
+
+    // the trainer is left untouched when the checkpoint doesn't match it
+    fn restore<N: Differentiable<Scalar = T>>(
+        &mut self,
+        params: &mut [N],
+        checkpoint: Checkpoint,
+    ) -> Result<(), CheckpointError> {
+        checkpoint.check::<Opt>(&self.model_id, params.len())?;
+        self.optimizer
+            .set_state(checkpoint.optimizer_state, params.len())?;
+
+        for (param, value) in params.iter_mut().zip(checkpoint.params) {
+            param.set_real(T::from_f64(value));
+        }
+        self.last_cost = checkpoint.last_cost.map(T::from_f64);
+        self.step_count = checkpoint.step_count;
+
+        Ok(())
+    }
+
+    // train_step of both trainers, `translate` is their param translator on slices
+    #[allow(clippy::too_many_arguments)]
+    fn train_step<
+        const PARALELIZE: bool,
+        const VERBOSE: bool,
+        const I: usize,
+        N: Differentiable<Scalar = T>,
+        D: Dataset<I, O, T> + ?Sized,
+        E: Dataset<I, O, T> + ?Sized,
+    >(
+        &mut self,
+        params: &mut [N],
+        model_gradient: impl Fn(&[N], &[T; I], &ExtraData) -> [N; O] + Sync,
+        model: impl Fn(&[T], &[T; I], &ExtraData) -> [T; O] + Sync,
+        translate: impl Fn(&[T], &[T]) -> Vec<T>,
+        dir_dataset: &D,
+        full_dataset: &E,
+    ) -> Result<StepReport<T>, TrainError> {
+        let t0 = Instant::now();
+        let param_count = params.len();
+
+        let cost: N = dataset_cost::<VERBOSE, false, PARALELIZE, _, _, _, _, _, _, _, _>(
+            dir_dataset,
+            &*params,
+            model_gradient,
+            &self.extra_data,
+            &self.loss,
+            self.output_weights.as_ref(),
+            self.thread_pool.as_deref(),
+        )
+        .map_err(|err| {
+            err.into_train_error(|cost| non_finite_indices(&get_gradient(cost, param_count)))
+        })?;
+
+        let gradient = get_gradient(&cost, param_count);
+
+        let report = optimizer_step::<VERBOSE, _, _, _, _>(
+            &mut self.optimizer,
+            params,
+            cost.get_real(),
+            gradient,
+            &self.gradient_clipping,
+            &self.regularization,
+            translate,
+            |new_params| {
+                dataset_cost::<false, false, PARALELIZE, _, _, _, _, _, _, _, _>(
+                    full_dataset,
+                    new_params,
+                    &model,
+                    &self.extra_data,
+                    &self.loss,
+                    self.output_weights.as_ref(),
+                    self.thread_pool.as_deref(),
+                )
+                .or_else(CostError::nan_if_non_finite)
+            },
+            t0,
+        )?;
+
+        self.last_cost = Some(report.cost);
+        if report.outcome == StepOutcome::Stepped {
+            self.step_count += 1;
+        }
+        self.last_report = Some(report.clone());
+
+        Ok(report)
+    }
+
+    // the validation that closes an epoch
+    fn validate<
+        const PARALELIZE: bool,
+        const VERBOSE: bool,
+        const I: usize,
+        N: Differentiable<Scalar = T>,
+        V: Dataset<I, O, T> + ?Sized,
+    >(
+        &mut self,
+        params: &mut [N],
+        model: impl Fn(&[T], &[T; I], &ExtraData) -> [T; O] + Sync,
+        validation: &V,
+    ) -> Result<EpochOutcome, TrainError> {
+        let values: Vec<T> = params.iter().map(|p| p.get_real()).collect();
+        let validation_cost = dataset_cost::<false, false, PARALELIZE, _, _, _, _, _, _, _, _>(
+            validation,
+            values.as_slice(),
+            model,
+            &self.extra_data,
+            &self.loss,
+            self.output_weights.as_ref(),
+            self.thread_pool.as_deref(),
+        )
+        .or_else(CostError::nan_if_non_finite)?;
+
+        let outcome = self.early_stopping.update(validation_cost, &values);
+
+        if VERBOSE {
+            println!("validation: {outcome:?}");
+        }
+
+        if let EpochOutcome::Stopped { best_cost: Some(_) } = outcome {
+            for (param, best) in params.iter_mut().zip(self.early_stopping.best_params()) {
+                param.set_real(T::from_f64(*best));
+            }
+        }
+
+        Ok(outcome)
+    }
+}
+
+// Trainer models take [X; P], TrainerBase hands them slices that always hold P elements
+fn on_slices<
+    const P: usize,
+    const I: usize,
+    const O: usize,
+    X,
+    T,
+    ExtraData,
+    M: Fn(&[X; P], &[T; I], &ExtraData) -> [X; O] + Sync,
+>(
+    model: &M,
+) -> impl Fn(&[X], &[T; I], &ExtraData) -> [X; O] + Sync + '_ {
+    move |params: &[X], input: &[T; I], extra: &ExtraData| {
+        model(params.try_into().unwrap(), input, extra)
+    }
+}
+
+// builds [T; P] straight on the heap, [Dual<P, _>; P] is too big for the stack of a thread
+fn boxed_array<T, const P: usize>(f: impl FnMut(usize) -> T) -> Box<[T; P]> {
+    match (0..P).map(f).collect::<Box<[T]>>().try_into() {
+        Ok(array) => array,
+        Err(_) => unreachable!(),
+    }
+}
+
+pub fn default_param_translator<const P: usize, T: Scalar>(
+    params: &[T; P],
+    vector: &[T; P],
+) -> [T; P] {
     array::from_fn(|i| params[i] + vector[i])
 }
 
-pub fn param_translator_with_bounds<const P: usize, const MAX: isize, const MIN: isize>(
-    params: &[f32; P],
-    vector: &[f32; P],
-) -> [f32; P] {
-    array::from_fn(|i| (params[i] + vector[i]).min(MAX as f32).max(MIN as f32))
+pub fn param_translator_with_bounds<
+    const P: usize,
+    const MAX: isize,
+    const MIN: isize,
+    T: Scalar,
+>(
+    params: &[T; P],
+    vector: &[T; P],
+) -> [T; P] {
+    array::from_fn(|i| {
+        (params[i] + vector[i])
+            .min(T::from(MAX as f32))
+            .max(T::from(MIN as f32))
+    })
 }
 
 #[derive(Clone)]
@@ -144,17 +610,17 @@ pub struct Trainer<
     const I: usize,
     const O: usize,
     ExtraData: Sync + Clone,
-    S: SimdArr<P>,
-    FG: Fn(&[Dual<P, S>; P], &[f32; I], &ExtraData) -> [Dual<P, S>; O] + Sync + Clone,
-    F: Fn(&[f32; P], &[f32; I], &ExtraData) -> [f32; O] + Sync + Clone,
-    ParamTranslate: Fn(&[f32; P], &[f32; P]) -> [f32; P] + Clone,
+    N: Differentiable,
+    FG: Fn(&[N; P], &[N::Scalar; I], &ExtraData) -> [N; O] + Sync + Clone,
+    F: Fn(&[N::Scalar; P], &[N::Scalar; I], &ExtraData) -> [N::Scalar; O] + Sync + Clone,
+    ParamTranslate: Fn(&[N::Scalar; P], &[N::Scalar; P]) -> [N::Scalar; P] + Clone,
+    Opt: Optimizer,
 > {
     model_gradient: FG,
     model: F,
-    params: [Dual<P, S>; P],
+    params: Box<[N; P]>,
     param_translator: ParamTranslate,
-    extra_data: ExtraData,
-    last_cost: Option<f32>,
+    base: TrainerBase<N::Scalar, O, ExtraData, Opt>,
 }
 
 impl<
@@ -162,37 +628,52 @@ impl<
         const I: usize,
         const O: usize,
         ExtraData: Sync + Clone,
-        FG: Fn(&[Dual<P, DenseSimd<P>>; P], &[f32; I], &ExtraData) -> [Dual<P, DenseSimd<P>>; O]
-            + Sync
-            + Clone,
-        F: Fn(&[f32; P], &[f32; I], &ExtraData) -> [f32; O] + Sync + Clone,
-        ParamTranslate: Fn(&[f32; P], &[f32; P]) -> [f32; P] + Clone,
-    > Trainer<P, I, O, ExtraData, DenseSimd<P>, FG, F, ParamTranslate>
+        N: Differentiable,
+        FG: Fn(&[N; P], &[N::Scalar; I], &ExtraData) -> [N; O] + Sync + Clone,
+        F: Fn(&[N::Scalar; P], &[N::Scalar; I], &ExtraData) -> [N::Scalar; O] + Sync + Clone,
+        ParamTranslate: Fn(&[N::Scalar; P], &[N::Scalar; P]) -> [N::Scalar; P] + Clone,
+    > Trainer<P, I, O, ExtraData, N, FG, F, ParamTranslate, AsintoticSearch>
 {
-    pub fn new_dense(
+    pub fn new(
         trainable: F,
         trainable_gradient: FG,
         param_translator: ParamTranslate,
         extra_data: ExtraData,
     ) -> Self {
-        rayon::ThreadPoolBuilder::new()
-            .stack_size(1 * 1024 * 1024 * 1024)
-            .build_global()
-            .unwrap();
-
-        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let mut base = TrainerBase::new(extra_data);
 
         Self {
             model_gradient: trainable_gradient,
             model: trainable,
-            params: array::from_fn(|i| Dual::new_param(rng.gen::<f32>() - 0.5, i)),
+            params: boxed_array(|i| base.random_param(i)),
             param_translator,
-            extra_data,
-            last_cost: None,
+            base,
         }
     }
 }
 
+impl<
+        const P: usize,
+        const I: usize,
+        const O: usize,
+        ExtraData: Sync + Clone,
+        FG: Fn(&[Dual<P, DenseSimd<P>>; P], &[f32; I], &ExtraData) -> [Dual<P, DenseSimd<P>>; O]
+            + Sync
+            + Clone,
+        F: Fn(&[f32; P], &[f32; I], &ExtraData) -> [f32; O] + Sync + Clone,
+        ParamTranslate: Fn(&[f32; P], &[f32; P]) -> [f32; P] + Clone,
+    > Trainer<P, I, O, ExtraData, Dual<P, DenseSimd<P>>, FG, F, ParamTranslate, AsintoticSearch>
+{
+    pub fn new_dense(
+        trainable: F,
+        trainable_gradient: FG,
+        param_translator: ParamTranslate,
+        extra_data: ExtraData,
+    ) -> Self {
+        Self::new(trainable, trainable_gradient, param_translator, extra_data)
+    }
+}
+
 impl<
         const P: usize,
         const I: usize,
@@ -208,7 +689,18 @@ impl<
             + Clone,
         F: Fn(&[f32; P], &[f32; I], &ExtraData) -> [f32; O] + Sync + Clone,
         ParamTranslate: Fn(&[f32; P], &[f32; P]) -> [f32; P] + Clone,
-    > Trainer<P, I, O, ExtraData, HybridSimd<P, CRITIALITY>, FG, F, ParamTranslate>
+    >
+    Trainer<
+        P,
+        I,
+        O,
+        ExtraData,
+        Dual<P, HybridSimd<P, CRITIALITY>>,
+        FG,
+        F,
+        ParamTranslate,
+        AsintoticSearch,
+    >
 {
     pub fn new_hybrid(
         _: CriticalityCue<CRITIALITY>,
@@ -217,20 +709,7 @@ impl<
         param_translator: ParamTranslate,
         extra_data: ExtraData,
     ) -> Self {
-        rayon::ThreadPoolBuilder::new()
-            .stack_size(1 * 1024 * 1024 * 1024)
-            .build_global();
-
-        let mut rng = ChaCha8Rng::seed_from_u64(2);
-
-        Self {
-            model_gradient: trainable_gradient,
-            model: trainable,
-            params: array::from_fn(|i| Dual::new_param(rng.gen::<f32>() - 0.5, i)),
-            param_translator,
-            extra_data,
-            last_cost: None,
-        }
+        Self::new(trainable, trainable_gradient, param_translator, extra_data)
     }
 }
 
@@ -239,187 +718,875 @@ impl<
         const I: usize,
         const O: usize,
         ExtraData: Sync + Clone,
-        S: SimdArr<P>,
-        FG: Fn(&[Dual<P, S>; P], &[f32; I], &ExtraData) -> [Dual<P, S>; O] + Sync + Clone,
+        FG: Fn(&[Reverse; P], &[f32; I], &ExtraData) -> [Reverse; O] + Sync + Clone,
         F: Fn(&[f32; P], &[f32; I], &ExtraData) -> [f32; O] + Sync + Clone,
-        ParamTranslate: Fn(&[f32; P], &[f32; P]) -> [f32; P] + Sync + Clone,
-    > Trainer<P, I, O, ExtraData, S, FG, F, ParamTranslate>
+        ParamTranslate: Fn(&[f32; P], &[f32; P]) -> [f32; P] + Clone,
+    > Trainer<P, I, O, ExtraData, Reverse, FG, F, ParamTranslate, AsintoticSearch>
 {
-    pub fn get_model_params(&self) -> [f32; P] {
-        self.params.clone().map(|e| e.get_real())
+    pub fn new_reverse(
+        trainable: F,
+        trainable_gradient: FG,
+        param_translator: ParamTranslate,
+        extra_data: ExtraData,
+    ) -> Self {
+        Self::new(trainable, trainable_gradient, param_translator, extra_data)
     }
+}
 
-    pub fn save(&self, file_path: &str) -> std::io::Result<()> {
-        let mut file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(file_path)?;
-
-        for p in self.params.iter() {
-            file.write(format!("{}\n", p.get_real()).as_bytes())?;
+impl<
+        const P: usize,
+        const I: usize,
+        const O: usize,
+        ExtraData: Sync + Clone,
+        N: Differentiable,
+        FG: Fn(&[N; P], &[N::Scalar; I], &ExtraData) -> [N; O] + Sync + Clone,
+        F: Fn(&[N::Scalar; P], &[N::Scalar; I], &ExtraData) -> [N::Scalar; O] + Sync + Clone,
+        ParamTranslate: Fn(&[N::Scalar; P], &[N::Scalar; P]) -> [N::Scalar; P] + Sync + Clone,
+        Opt: Optimizer,
+    > Trainer<P, I, O, ExtraData, N, FG, F, ParamTranslate, Opt>
+{
+    pub fn with_optimizer<NewOpt: Optimizer>(
+        self,
+        optimizer: NewOpt,
+    ) -> Trainer<P, I, O, ExtraData, N, FG, F, ParamTranslate, NewOpt> {
+        Trainer {
+            model_gradient: self.model_gradient,
+            model: self.model,
+            params: self.params,
+            param_translator: self.param_translator,
+            base: self.base.with_optimizer(optimizer),
         }
+    }
 
-        Ok(())
+    pub fn with_loss(mut self, loss: Loss) -> Self {
+        self.base.loss = loss;
+        self
     }
 
-    pub fn load(&mut self, file_path: &str) -> std::io::Result<()> {
-        let file = OpenOptions::new()
-            .write(true)
-            .read(true)
-            .create(true)
-            .open(file_path)?;
-        let reader = io::BufReader::new(file);
+    // multiplies the loss of each output, on top of the weights of the data points
+    pub fn with_output_weights(mut self, output_weights: [N::Scalar; O]) -> Self {
+        self.base.output_weights = Some(output_weights);
+        self
+    }
 
-        for (i, line) in reader.lines().enumerate() {
-            let line = line?;
-            if i < self.params.len() {
-                if let Ok(param) = line.parse::<f32>() {
-                    self.params[i].set_real(param);
-                } else {
-                    eprintln!("Failed to parse line: {}", line);
-                }
-            } else {
-                break;
-            }
-        }
+    // stored in checkpoints, loading a checkpoint of another model fails
+    pub fn with_model_id(mut self, model_id: &str) -> Self {
+        self.base.model_id = model_id.to_string();
+        self
+    }
 
-        Ok(())
+    pub fn with_early_stopping(mut self, early_stopping: EarlyStopping) -> Self {
+        self.base.early_stopping = early_stopping;
+        self
+    }
+
+    pub fn with_gradient_clipping(mut self, gradient_clipping: GradientClipping) -> Self {
+        self.base.gradient_clipping = gradient_clipping;
+        self
+    }
+
+    pub fn with_regularization(mut self, regularization: Regularization) -> Self {
+        regularization.check_len(P);
+        self.base.regularization = regularization;
+        self
+    }
+
+    // reinitializes the params, two trainers with the same seed and the same calls end up with
+    // bit-identical params
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.base.seed = seed;
+        self.base.initialize_params(&mut *self.params);
+        self
+    }
+
+    // reinitializes the params, with_seed and with_initializer can come in any order
+    pub fn with_initializer(mut self, initializer: Initializer<N::Scalar>) -> Self {
+        self.base.initializer = initializer;
+        self.base.initialize_params(&mut *self.params);
+        self
+    }
+
+    // the dataset costs run on this pool instead of the global one, trainers can share it
+    pub fn with_thread_pool(mut self, thread_pool: Arc<ThreadPool>) -> Self {
+        self.base.thread_pool = Some(thread_pool);
+        self
+    }
+
+    // same as with_thread_pool with a pool of its own
+    pub fn with_threads(self, threads: usize) -> Result<Self, ThreadPoolBuildError> {
+        let thread_pool = ThreadPoolBuilder::new().num_threads(threads).build()?;
+        Ok(self.with_thread_pool(Arc::new(thread_pool)))
+    }
+
+    pub fn get_early_stopping(&self) -> &EarlyStopping {
+        &self.base.early_stopping
+    }
+
+    pub fn get_optimizer(&self) -> &Opt {
+        &self.base.optimizer
+    }
+
+    // amount of train steps that updated the params
+    pub fn get_step_count(&self) -> u64 {
+        self.base.step_count
+    }
+
+    pub fn get_model_params(&self) -> [N::Scalar; P] {
+        array::from_fn(|i| self.params[i].get_real())
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.base.checkpoint(&*self.params)
+    }
+
+    // the trainer is left untouched when the checkpoint doesn't match it
+    pub fn restore(&mut self, checkpoint: Checkpoint) -> Result<(), CheckpointError> {
+        self.base.restore(&mut *self.params, checkpoint)
+    }
+
+    pub fn save(&self, file_path: &str) -> Result<(), CheckpointError> {
+        self.checkpoint().save(file_path)
+    }
+
+    pub fn load(&mut self, file_path: &str) -> Result<(), CheckpointError> {
+        self.restore(Checkpoint::load(file_path)?)
     }
 
     pub fn shake(&mut self, factor: f32) {
-        for i in 0..P {
-            self.params[i]
-                .set_real(self.params[i].get_real() + (rand::random::<f32>() - 0.5) * factor);
-        }
+        self.base.shake(&mut *self.params, factor)
     }
 
-    // TODO partition the dataset
+    // the optimizer state is kept, last_cost is dropped as it belongs to the old params
+    pub fn set_params(&mut self, params: &[N::Scalar; P]) {
+        self.base.set_params(&mut *self.params, params)
+    }
 
+    // one train_step per chunk of subdataset_size data points, the line search runs on the whole
+    // dataset
     pub fn train_stocastic_step<
         const PARALELIZE: bool,
         const VERBOSE: bool,
+        D: Dataset<I, O, N::Scalar> + ?Sized,
         CB: Fn(usize, &mut Self),
     >(
         &mut self,
-        dataset: &Vec<DataPoint<P, I, O>>,
+        dataset: &D,
         subdataset_size: usize,
         inter_step_callback: CB,
-    ) -> bool {
-        let mut ret = false;
-        for (i, sub_dataset) in dataset.chunks(subdataset_size).enumerate() {
-            self.last_cost = None;
-            ret |= self.train_step_asintotic_search::<PARALELIZE, VERBOSE, _, _>(
-                sub_dataset,
-                dataset,
-                sub_dataset.len(),
-                dataset.len(),
-            );
+    ) -> Result<StepOutcome, TrainError> {
+        let mut ret = StepOutcome::Stalled;
+        for (i, start) in (0..dataset.len()).step_by(subdataset_size).enumerate() {
+            let sub_dataset = dataset.range(start..(start + subdataset_size).min(dataset.len()));
+            self.base.last_cost = None;
+            let report = self
+                .train_step::<PARALELIZE, VERBOSE, _, _>(&sub_dataset, dataset)
+                .map_err(|err| err.offset_data_point(start))?;
+
+            if report.outcome == StepOutcome::Stepped {
+                ret = StepOutcome::Stepped;
+            }
             inter_step_callback(i, self);
         }
 
-        ret
+        Ok(ret)
     }
 
-    // TODO adam
-
-    // TODO return a proper error when NaN apears
+    // one train_step on batch_size data points sampled from the dataset, the line search runs on
+    // the same batch. Only the batch is held in memory so the dataset can be generated or read
+    // from disk as it goes.
+    pub fn train_minibatch_step<
+        const PARALELIZE: bool,
+        const VERBOSE: bool,
+        D: Dataset<I, O, N::Scalar> + ?Sized,
+    >(
+        &mut self,
+        dataset: &D,
+        batch_size: usize,
+    ) -> Result<StepReport<N::Scalar>, TrainError> {
+        let (indices, batch) = minibatch(dataset, batch_size, &mut self.base.rng)?;
+        // every batch has a different cost, it can't be compared with the last one
+        self.base.last_cost = None;
+        self.train_step::<PARALELIZE, VERBOSE, _, _>(&batch, &batch)
+            .map_err(|err| err.map_data_point(|i| indices[i]))
+    }
 
-    pub fn train_step_asintotic_search<
-        'a,
-        'b,
+    // one train_stocastic_step followed by the validation of the resulting params. Once the
+    // early stopping patience runs out the params are reset to the best validated ones.
+    pub fn train_epoch<
         const PARALELIZE: bool,
         const VERBOSE: bool,
-        D: IntoIterator<Item = &'b DataPoint<P, I, O>>
-            + IntoParallelIterator<Item = &'a DataPoint<P, I, O>>
-            + Clone,
-        E: IntoIterator<Item = &'b DataPoint<P, I, O>>
-            + IntoParallelIterator<Item = &'a DataPoint<P, I, O>>
-            + Clone,
+        D: Dataset<I, O, N::Scalar> + ?Sized,
+        V: Dataset<I, O, N::Scalar> + ?Sized,
+        CB: Fn(usize, &mut Self),
     >(
         &mut self,
-        dir_dataset: D,
-        full_dataset: E,
-        dir_dataset_len: usize,
-        full_dataset_len: usize,
-    ) -> bool {
-        let t0 = Instant::now();
+        dataset: &D,
+        validation: &V,
+        subdataset_size: usize,
+        inter_step_callback: CB,
+    ) -> Result<EpochOutcome, TrainError> {
+        self.train_stocastic_step::<PARALELIZE, VERBOSE, _, _>(
+            dataset,
+            subdataset_size,
+            inter_step_callback,
+        )?;
+
+        self.base.validate::<PARALELIZE, VERBOSE, _, _, _>(
+            &mut *self.params,
+            on_slices(&self.model),
+            validation,
+        )
+    }
 
-        let cost: Dual<P, S> = dataset_cost::<VERBOSE, false, PARALELIZE, _, _, _, _, _, _, _>(
+    // the gradient comes from dir_dataset, the line search evaluates full_dataset
+    pub fn train_step<
+        const PARALELIZE: bool,
+        const VERBOSE: bool,
+        D: Dataset<I, O, N::Scalar> + ?Sized,
+        E: Dataset<I, O, N::Scalar> + ?Sized,
+    >(
+        &mut self,
+        dir_dataset: &D,
+        full_dataset: &E,
+    ) -> Result<StepReport<N::Scalar>, TrainError> {
+        let param_translator = &self.param_translator;
+        self.base.train_step::<PARALELIZE, VERBOSE, _, _, _, _>(
+            &mut *self.params,
+            on_slices(&self.model_gradient),
+            on_slices(&self.model),
+            |og_parameters, displacement| {
+                param_translator(
+                    og_parameters.try_into().unwrap(),
+                    displacement.try_into().unwrap(),
+                )
+                .to_vec()
+            },
             dir_dataset,
-            dir_dataset_len,
-            &self.params,
-            &self.model_gradient,
-            &self.extra_data,
+            full_dataset,
+        )
+    }
+
+    // Hessian of the cost over `dataset` times `vector`, at the current params. `model` is the
+    // model instantiated for hyper duals (ie: the generic fn behind trainable_gradient). The where
+    // clause holds for any SimdArr over the scalar of the trainer.
+    pub fn hessian_vector_product<
+        const PARALELIZE: bool,
+        S: SimdArr<P, Scalar = N::Scalar>,
+        H: Fn(&[HyperDual<P, S>; P], &[N::Scalar; I], &ExtraData) -> [HyperDual<P, S>; O] + Sync,
+        D: Dataset<I, O, N::Scalar> + ?Sized,
+    >(
+        &self,
+        model: H,
+        dataset: &D,
+        vector: &[N::Scalar; P],
+    ) -> Result<[N::Scalar; P], TrainError>
+    where
+        HyperDual<P, S>: Add<N::Scalar, Output = HyperDual<P, S>>
+            + Sub<N::Scalar, Output = HyperDual<P, S>>
+            + Mul<N::Scalar, Output = HyperDual<P, S>>
+            + Div<N::Scalar, Output = HyperDual<P, S>>
+            + PartialOrd<N::Scalar>,
+    {
+        let params: Box<[HyperDual<P, S>; P]> =
+            boxed_array(|i| HyperDual::new_param(self.params[i].get_real(), i, vector[i]));
+
+        let cost = dataset_cost::<false, false, PARALELIZE, _, _, _, _, _, _, _, _>(
+            dataset,
+            &*params,
+            model,
+            &self.base.extra_data,
+            &self.base.loss,
+            self.base.output_weights.as_ref(),
+            self.base.thread_pool.as_deref(),
+        )
+        .map_err(|err| {
+            err.into_train_error(|cost| non_finite_indices(&cost.get_hessian_vector_product()))
+        })?;
+
+        Ok(cost.get_hessian_vector_product())
+    }
+
+    // takes one hessian_vector_product per param, meant for small models
+    pub fn hessian_diagonal<
+        const PARALELIZE: bool,
+        S: SimdArr<P, Scalar = N::Scalar>,
+        H: Fn(&[HyperDual<P, S>; P], &[N::Scalar; I], &ExtraData) -> [HyperDual<P, S>; O] + Sync,
+        D: Dataset<I, O, N::Scalar> + ?Sized,
+    >(
+        &self,
+        model: H,
+        dataset: &D,
+    ) -> Result<[N::Scalar; P], TrainError>
+    where
+        HyperDual<P, S>: Add<N::Scalar, Output = HyperDual<P, S>>
+            + Sub<N::Scalar, Output = HyperDual<P, S>>
+            + Mul<N::Scalar, Output = HyperDual<P, S>>
+            + Div<N::Scalar, Output = HyperDual<P, S>>
+            + PartialOrd<N::Scalar>,
+    {
+        let mut ret = [N::Scalar::ZERO; P];
+        for (i, x) in ret.iter_mut().enumerate() {
+            let direction = array::from_fn(|j| {
+                if i == j {
+                    N::Scalar::ONE
+                } else {
+                    N::Scalar::ZERO
+                }
+            });
+            *x =
+                self.hessian_vector_product::<PARALELIZE, _, _, _>(&model, dataset, &direction)?[i];
+        }
+        Ok(ret)
+    }
+
+    pub fn get_last_cost(&self) -> Option<N::Scalar> {
+        self.base.last_cost
+    }
+
+    // report of the last train step, train_stocastic_step callbacks get the one of their chunk
+    pub fn get_last_report(&self) -> Option<&StepReport<N::Scalar>> {
+        self.base.last_report.as_ref()
+    }
+
+    pub fn eval(&self, input: &[N::Scalar; I]) -> [N::Scalar; O] {
+        (self.model)(&self.get_model_params(), input, &self.base.extra_data)
+    }
+}
+
+#[cfg(test)]
+mod trainer_tests {
+    use std::ops::{Add, Div, Mul};
+    use std::sync::Arc;
+
+    use rayon::ThreadPoolBuilder;
+
+    use super::{default_param_translator, CriticalityCue, DataPoint, StepOutcome, Trainer};
+    use crate::dual::Dual;
+    use crate::simd_arr::dense_simd::DenseSimd;
+    use crate::simd_arr::hybrid_simd::HybridSimd;
+    use crate::trainer::error::TrainError;
+    use crate::trainer::gradient::GradientClipping;
+    use crate::trainer::initializer::Initializer;
+    use crate::trainer::loss::Loss;
+    use crate::trainer::optimizer::adam::{Adam, AdamConfig};
+    use crate::trainer::optimizer::asintotic_search::AsintoticSearch;
+    use crate::trainer::optimizer::sgd::Sgd;
+    use crate::trainer::regularization::Regularization;
+
+    fn linear<T: Copy, N: Clone + Add<N, Output = N> + Mul<T, Output = N>>(
+        params: &[N; 2],
+        input: &[T; 1],
+        _: &(),
+    ) -> [N; 1] {
+        [params[0].clone() * input[0] + params[1].clone()]
+    }
+
+    type LineDual = Dual<2, HybridSimd<2, 2>>;
+    type LineModel<N> = fn(&[N; 2], &[f32; 1], &()) -> [N; 1];
+    type LineTrainer = Trainer<
+        2,
+        1,
+        1,
+        (),
+        LineDual,
+        LineModel<LineDual>,
+        LineModel<f32>,
+        fn(&[f32; 2], &[f32; 2]) -> [f32; 2],
+        AsintoticSearch,
+    >;
+
+    // the trainer most tests fit the line with
+    fn line_trainer() -> LineTrainer {
+        Trainer::new_hybrid(
+            CriticalityCue::<2>(),
+            linear,
+            linear,
+            default_param_translator,
+            (),
+        )
+    }
+
+    fn line_dataset() -> Vec<DataPoint<1, 1>> {
+        (-10..10)
+            .map(|x| x as f32 / 10.)
+            .map(|x| DataPoint::new([x], [2. * x + 1.]))
+            .collect()
+    }
+
+    #[test]
+    fn adam_fits_a_line() {
+        let dataset = line_dataset();
+
+        let mut trainer = line_trainer().with_optimizer(Adam::new(AdamConfig {
+            learning_rate: 0.01,
+            ..Default::default()
+        }));
+
+        for _ in 0..2000 {
+            trainer
+                .train_step::<false, false, _, _>(&dataset, &dataset)
+                .unwrap();
+        }
+
+        let [slope, intercept] = trainer.get_model_params();
+
+        assert!((slope - 2.).abs() < 0.05, "slope {slope}");
+        assert!((intercept - 1.).abs() < 0.05, "intercept {intercept}");
+    }
+
+    // the third param is left out of the model
+    fn line_and_dead_param<T: Copy, N: Clone + Add<N, Output = N> + Mul<T, Output = N>>(
+        params: &[N; 3],
+        input: &[T; 1],
+        extra: &(),
+    ) -> [N; 1] {
+        linear(&[params[0].clone(), params[1].clone()], input, extra)
+    }
+
+    #[test]
+    fn step_report_and_clipping() {
+        let dataset = line_dataset();
+
+        let mut trainer = Trainer::new_hybrid(
+            CriticalityCue::<3>(),
+            line_and_dead_param,
+            line_and_dead_param,
+            default_param_translator,
+            (),
+        )
+        .with_optimizer(Sgd::new(1.))
+        .with_gradient_clipping(GradientClipping {
+            max_value: Some(0.01),
+            ..Default::default()
+        });
+        let og_params = trainer.get_model_params();
+
+        let report = trainer
+            .train_step::<false, false, _, _>(&dataset, &dataset)
+            .unwrap();
+
+        assert_eq!(report.outcome, StepOutcome::Stepped);
+        assert_eq!(report.zero_gradients, 1);
+        assert!(report.clipped);
+        assert!(report.gradient_norm > 0.01);
+        assert_eq!(trainer.get_last_report(), Some(&report));
+        assert_eq!(trainer.get_last_cost(), Some(report.cost));
+
+        // sgd moves each param by its clipped gradient
+        for (og_param, param) in og_params.iter().zip(trainer.get_model_params()) {
+            assert!((og_param - param).abs() <= 0.01 + 1e-6);
+        }
+    }
+
+    #[test]
+    fn l2_regularization_matches_ridge() {
+        let dataset = line_dataset();
+        let l2 = 0.5;
+
+        let mut trainer = line_trainer()
+            .with_optimizer(Sgd::new(0.3))
+            .with_loss(Loss::L2)
+            // only the slope
+            .with_regularization(Regularization {
+                l2,
+                mask: Some(vec![true, false]),
+                ..Default::default()
+            });
+
+        for _ in 0..2000 {
+            trainer
+                .train_step::<false, false, _, _>(&dataset, &dataset)
+                .unwrap();
+        }
+
+        // with the data on y = 2x + 1 the minimum of MSE + l2 * slope^2 is shifted by
+        // -2 * l2 / (var(x) + l2) on the slope and -mean(x) times that on the intercept
+        let n = dataset.len() as f32;
+        let mean_x = dataset.iter().map(|p| p.input[0]).sum::<f32>() / n;
+        let var_x =
+            dataset.iter().map(|p| p.input[0] * p.input[0]).sum::<f32>() / n - mean_x * mean_x;
+        let shift = -2. * l2 / (var_x + l2);
+
+        let [slope, intercept] = trainer.get_model_params();
+        assert!((slope - (2. + shift)).abs() < 1e-3, "slope {slope}");
+        assert!(
+            (intercept - (1. - mean_x * shift)).abs() < 1e-3,
+            "intercept {intercept}"
         );
+    }
 
-        let fast_full_cost: f32 = dataset_cost::<false, false, PARALELIZE, _, _, _, _, _, _, _>(
-            full_dataset.clone(),
-            full_dataset_len,
-            &self.params.clone().map(|x| x.get_real()),
-            &self.model,
-            &self.extra_data,
+    #[test]
+    fn weight_decay_pulls_unused_params_to_zero() {
+        let dataset = line_dataset();
+
+        let mut trainer = Trainer::new_hybrid(
+            CriticalityCue::<3>(),
+            line_and_dead_param,
+            line_and_dead_param,
+            default_param_translator,
+            (),
+        )
+        .with_initializer(Initializer::Constant(1.))
+        .with_optimizer(Sgd::new(0.1))
+        .with_regularization(Regularization {
+            weight_decay: 0.1,
+            mask: Some(vec![false, true, true]),
+            ..Default::default()
+        });
+
+        for _ in 0..10 {
+            trainer
+                .train_step::<false, false, _, _>(&dataset, &dataset)
+                .unwrap();
+        }
+
+        // nothing but the decay moves the dead param
+        assert!((trainer.get_model_params()[2] - 0.9f32.powi(10)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn weights_count_like_repeated_points() {
+        let line = line_dataset();
+        let weighted: Vec<_> = line
+            .iter()
+            .enumerate()
+            .map(|(i, point)| point.with_weight(if i == 3 { 3. } else { 1. }))
+            .collect();
+        let repeated: Vec<_> = line.iter().chain([&line[3], &line[3]]).copied().collect();
+
+        let step = |dataset: &Vec<DataPoint<1, 1>>, output_weight| {
+            let mut trainer = line_trainer()
+                .with_optimizer(Sgd::new(0.1))
+                .with_output_weights([output_weight]);
+
+            let report = trainer
+                .train_step::<false, false, _, _>(dataset, dataset)
+                .unwrap();
+            (report.cost, trainer.get_model_params())
+        };
+
+        let (weighted_cost, weighted_params) = step(&weighted, 1.);
+        let (repeated_cost, repeated_params) = step(&repeated, 1.);
+        assert!((weighted_cost - repeated_cost).abs() < 1e-5);
+        for (w, r) in weighted_params.iter().zip(repeated_params) {
+            assert!((w - r).abs() < 1e-5, "{w} vs {r}");
+        }
+
+        // the report cost is the one before the step, it scales with the output weight
+        let (doubled_cost, _) = step(&weighted, 2.);
+        assert!((doubled_cost - 2. * weighted_cost).abs() < 1e-5);
+    }
+
+    #[test]
+    fn weightless_datasets_are_an_error() {
+        let line = line_dataset();
+        let weightless: Vec<_> = line.iter().map(|point| point.with_weight(0.)).collect();
+
+        let mut trainer = line_trainer();
+        let og_params = trainer.get_model_params();
+
+        for (dir_dataset, full_dataset) in [(&weightless, &line), (&line, &weightless)] {
+            assert_eq!(
+                trainer.train_step::<false, false, _, _>(dir_dataset, full_dataset),
+                Err(TrainError::NoWeight)
+            );
+        }
+        assert_eq!(
+            trainer.train_step::<false, false, _, _>(&line[..0], &line[..0]),
+            Err(TrainError::NoWeight)
         );
+        assert_eq!(trainer.get_model_params(), og_params);
+    }
+
+    #[test]
+    fn invalid_weights_are_an_error() {
+        let line = line_dataset();
+        let mut trainer = line_trainer();
+        let og_params = trainer.get_model_params();
 
-        let mut factor = 1.;
+        for weight in [-1., f32::NAN, f32::INFINITY] {
+            let mut dataset = line.clone();
+            dataset[3] = DataPoint { weight, ..line[3] };
+            assert_eq!(
+                trainer.train_step::<false, false, _, _>(&dataset, &dataset),
+                Err(TrainError::InvalidWeight { data_point: 3 })
+            );
+        }
+        assert_eq!(trainer.get_model_params(), og_params);
+    }
 
-        let raw_gradient = cost.get_gradient();
-        let gradient_size: f32 = raw_gradient
+    #[test]
+    fn f64_fits_beyond_f32_precision() {
+        let dataset: Vec<DataPoint<1, 1, f64>> = line_dataset()
             .iter()
-            .fold(0., |acc, elm| acc + (elm * elm))
-            .max(1e-30);
+            .map(|point| {
+                DataPoint::new(
+                    point.input.map(f64::from),
+                    [2. * f64::from(point.input[0]) + 1.],
+                )
+            })
+            .collect();
+
+        let trainer: Trainer<_, _, _, _, Dual<2, HybridSimd<2, 2, f64>>, _, _, _, _> =
+            Trainer::new(linear, linear, default_param_translator, ());
+        let mut trainer = trainer.with_optimizer(Sgd::new(0.5)).with_loss(Loss::L2);
+
+        for _ in 0..500 {
+            trainer
+                .train_step::<false, false, _, _>(&dataset, &dataset)
+                .unwrap();
+        }
+
+        let [slope, intercept] = trainer.get_model_params();
+
+        assert!((slope - 2.).abs() < 1e-12, "slope {slope}");
+        assert!((intercept - 1.).abs() < 1e-12, "intercept {intercept}");
+    }
+
+    fn linear_on_3_threads<T: Copy, N: Clone + Add<N, Output = N> + Mul<T, Output = N>>(
+        params: &[N; 2],
+        input: &[T; 1],
+        extra: &(),
+    ) -> [N; 1] {
+        assert_eq!(rayon::current_num_threads(), 3);
+        linear(params, input, extra)
+    }
 
-        let unit_gradient = array::from_fn(|i| raw_gradient[i] / gradient_size.sqrt());
-        let og_parameters = array::from_fn(|i| self.params[i].get_real());
+    #[test]
+    fn costs_run_on_the_thread_pool() {
+        let dataset = line_dataset();
+        let thread_pool = Arc::new(ThreadPoolBuilder::new().num_threads(3).build().unwrap());
 
-        while {
-            let gradient = unit_gradient.map(|e| -e * factor);
+        let shared = (0..2).map(|_| {
+            Trainer::new_dense(
+                linear_on_3_threads,
+                linear_on_3_threads,
+                default_param_translator,
+                (),
+            )
+            .with_thread_pool(thread_pool.clone())
+        });
+        let own = Trainer::new_dense(
+            linear_on_3_threads,
+            linear_on_3_threads,
+            default_param_translator,
+            (),
+        )
+        .with_threads(3)
+        .unwrap();
 
-            let new_params = (self.param_translator)(&og_parameters, &gradient);
+        for trainer in shared.chain([own]) {
+            let mut trainer = trainer.with_optimizer(Sgd::new(0.1));
+            trainer
+                .train_epoch::<true, false, _, _, _>(&dataset, &dataset, 5, |_, _| {})
+                .unwrap();
 
-            for (i, param) in new_params.iter().enumerate() {
-                self.params[i].set_real(*param);
+            assert!(trainer.get_step_count() > 0);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_params() {
+        let dataset = line_dataset();
+
+        let run = |seed, threads| {
+            let mut trainer = line_trainer()
+                .with_seed(seed)
+                .with_threads(threads)
+                .unwrap();
+
+            for _ in 0..3 {
+                trainer
+                    .train_stocastic_step::<true, false, _, _>(&dataset, 5, |_, trainer| {
+                        trainer.shake(0.1)
+                    })
+                    .unwrap();
             }
+            trainer.get_model_params()
+        };
 
-            let new_cost: f32 = dataset_cost::<false, false, PARALELIZE, _, _, _, _, _, _, _>(
-                full_dataset.clone(),
-                full_dataset_len,
-                &new_params,
-                &self.model,
-                &self.extra_data,
-            );
-            self.last_cost = Some(new_cost);
+        // bit-identical whatever the thread count
+        assert_eq!(run(7, 1), run(7, 4));
+        assert_ne!(run(7, 1), run(8, 1));
+    }
+
+    #[test]
+    fn initializer_and_set_params() {
+        let normal = Initializer::Normal {
+            mean: 0.,
+            std_dev: 1.,
+        };
+
+        assert_eq!(
+            line_trainer()
+                .with_seed(3)
+                .with_initializer(normal.clone())
+                .get_model_params(),
+            line_trainer()
+                .with_initializer(normal)
+                .with_seed(3)
+                .get_model_params()
+        );
+
+        let trainer = line_trainer().with_initializer(Initializer::Uniform { min: 0., max: 1. });
+        assert!(trainer
+            .get_model_params()
+            .iter()
+            .all(|x| (0. ..1.).contains(x)));
+
+        let mut trainer =
+            trainer.with_initializer(Initializer::PerParam(Arc::new(|i, _| i as f32)));
+        assert_eq!(trainer.get_model_params(), [0., 1.]);
+
+        trainer.set_params(&[2., 1.]);
+        assert_eq!(trainer.eval(&[1.]), [3.]);
+    }
+
+    const WIDE: usize = 1024;
+
+    fn mean<N: Clone + Add<N, Output = N> + Mul<f32, Output = N>>(
+        params: &[N; WIDE],
+        input: &[f32; 1],
+        _: &(),
+    ) -> [N; 1] {
+        let sum = params[1..]
+            .iter()
+            .fold(params[0].clone(), |acc, x| acc + x.clone());
+        [sum * (input[0] / WIDE as f32)]
+    }
+
+    #[test]
+    fn dense_runs_on_a_default_stack() {
+        // the duals of the params alone would take 4 MiB if they were on the stack
+        std::thread::spawn(|| {
+            let dataset: Vec<DataPoint<1, 1>> = vec![DataPoint::new([1.], [1.])];
+
+            let mut trainer = Trainer::new_dense(mean, mean, default_param_translator, ())
+                .with_optimizer(Sgd::new(0.1));
+
+            trainer
+                .train_step::<false, false, _, _>(&dataset, &dataset)
+                .unwrap();
+
+            assert_eq!(trainer.get_step_count(), 1);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn reverse_matches_forward_mode() {
+        let dataset = line_dataset();
+
+        let mut forward = line_trainer().with_optimizer(Sgd::new(0.1));
+
+        let mut reverse = Trainer::new_reverse(linear, linear, default_param_translator, ())
+            .with_optimizer(Sgd::new(0.1));
 
-            new_cost >= fast_full_cost
-        } {
-            factor *= 0.7;
+        for _ in 0..20 {
+            forward
+                .train_step::<false, false, _, _>(&dataset, &dataset)
+                .unwrap();
+            reverse
+                .train_step::<true, false, _, _>(&dataset, &dataset)
+                .unwrap();
 
-            if factor < 1e-10 {
-                return false;
+            for (f, r) in forward
+                .get_model_params()
+                .iter()
+                .zip(reverse.get_model_params())
+            {
+                assert!((f - r).abs() < 1e-5, "{f} vs {r}");
             }
         }
+    }
 
-        if VERBOSE {
-            println!(
-                "gradient length: {gradient_size:?} - fast_full_cost: {} - new cost: {} - learning factor: {} - improvement {} - time {}",
-                fast_full_cost, self.last_cost.unwrap(), factor, fast_full_cost - self.last_cost.unwrap(), t0.elapsed().as_secs_f32()
-            );
+    #[test]
+    fn newton_step_solves_a_quadratic() {
+        let dataset = line_dataset();
+        let n = dataset.len() as f32;
+
+        let trainer = line_trainer().with_loss(Loss::L2);
+
+        // the L2 cost of a line is quadratic, H = 2 * mean([x^2, x], [x, 1])
+        let sum_x: f32 = dataset.iter().map(|p| p.input[0]).sum();
+        let sum_x2: f32 = dataset.iter().map(|p| p.input[0] * p.input[0]).sum();
+        let hessian = [[2. * sum_x2 / n, 2. * sum_x / n], [2. * sum_x / n, 2.]];
+
+        let columns = [[1., 0.], [0., 1.]].map(|direction| {
+            trainer
+                .hessian_vector_product::<true, DenseSimd<2>, _, _>(linear, &dataset, &direction)
+                .unwrap()
+        });
+        for i in 0..2 {
+            for j in 0..2 {
+                assert!((columns[j][i] - hessian[i][j]).abs() < 1e-4, "{columns:?}");
+            }
         }
 
-        return true;
-    }
+        let diagonal = trainer
+            .hessian_diagonal::<false, DenseSimd<2>, _, _>(linear, &dataset)
+            .unwrap();
+        assert!((diagonal[0] - hessian[0][0]).abs() < 1e-4);
+        assert!((diagonal[1] - hessian[1][1]).abs() < 1e-4);
+
+        let [a, b] = trainer.get_model_params();
+        let gradient = dataset.iter().fold([0.; 2], |acc, p| {
+            let residual = a * p.input[0] + b - p.output[0];
+            [
+                acc[0] + 2. * residual * p.input[0] / n,
+                acc[1] + 2. * residual / n,
+            ]
+        });
 
-    // TODO
+        // params - H^-1 * gradient
+        let det = hessian[0][0] * hessian[1][1] - hessian[0][1] * hessian[1][0];
+        let slope = a - (hessian[1][1] * gradient[0] - hessian[0][1] * gradient[1]) / det;
+        let intercept = b - (hessian[0][0] * gradient[1] - hessian[1][0] * gradient[0]) / det;
 
-    pub fn get_last_cost(&self) -> Option<f32> {
-        self.last_cost
+        assert!((slope - 2.).abs() < 1e-3, "slope {slope}");
+        assert!((intercept - 1.).abs() < 1e-3, "intercept {intercept}");
     }
 
-    pub fn eval(&self, input: &[f32; I]) -> [f32; O] {
-        (self.model)(
-            &self.params.clone().map(|e| e.get_real()),
-            input,
-            &self.extra_data,
-        )
+    fn inverse<N: Clone + Div<f32, Output = N>>(
+        params: &[N; 1],
+        input: &[f32; 1],
+        _: &(),
+    ) -> [N; 1] {
+        [params[0].clone() / input[0]]
+    }
+
+    #[test]
+    fn non_finite_cost_is_reported() {
+        let dataset: Vec<DataPoint<1, 1>> =
+            [1., 2., 0., 3.].map(|x| DataPoint::new([x], [1.])).to_vec();
+
+        let mut trainer = Trainer::new_hybrid(
+            CriticalityCue::<1>(),
+            inverse,
+            inverse,
+            default_param_translator,
+            (),
+        );
+
+        let og_params = trainer.get_model_params();
+
+        assert_eq!(
+            trainer.train_step::<false, false, _, _>(&dataset, &dataset),
+            Err(TrainError::NonFiniteCost {
+                data_point: 2,
+                parameters: vec![0]
+            })
+        );
+
+        assert_eq!(
+            trainer.train_stocastic_step::<false, false, _, _>(&dataset, 2, |_, _| {}),
+            Err(TrainError::NonFiniteCost {
+                data_point: 2,
+                parameters: vec![0]
+            })
+        );
+
+        assert_eq!(og_params, trainer.get_model_params());
     }
 }
@@ -0,0 +1,214 @@
+use savefile::{load_file, save_file};
+use savefile_derive::Savefile;
+
+use crate::trainer::error::CheckpointError;
+use crate::trainer::optimizer::{Optimizer, OptimizerState};
+
+// bump whenever Checkpoint changes, savefile refuses files written by a newer version
+pub const CHECKPOINT_VERSION: u32 = 0;
+
+// Everything needed to resume training where it was left. Values are f64 whatever the scalar
+// of the trainer, so a checkpoint loads into an f32 and an f64 version of the same model.
+#[derive(Savefile, Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub model_id: String,
+    pub params: Vec<f64>,
+    pub optimizer: String,
+    pub optimizer_state: OptimizerState,
+    pub last_cost: Option<f64>,
+    pub step_count: u64,
+}
+
+impl Checkpoint {
+    pub fn save(&self, file_path: &str) -> Result<(), CheckpointError> {
+        save_file(file_path, CHECKPOINT_VERSION, self).map_err(CheckpointError::Format)
+    }
+
+    pub fn load(file_path: &str) -> Result<Self, CheckpointError> {
+        load_file(file_path, CHECKPOINT_VERSION).map_err(CheckpointError::Format)
+    }
+
+    // errors unless the checkpoint was written by a trainer like the one described
+    pub(crate) fn check<Opt: Optimizer>(
+        &self,
+        model_id: &str,
+        param_count: usize,
+    ) -> Result<(), CheckpointError> {
+        if self.model_id != model_id {
+            return Err(CheckpointError::ModelId {
+                expected: model_id.to_string(),
+                found: self.model_id.clone(),
+            });
+        }
+
+        if self.params.len() != param_count {
+            return Err(CheckpointError::ParamCount {
+                expected: param_count,
+                found: self.params.len(),
+            });
+        }
+
+        if self.optimizer != Opt::NAME {
+            return Err(CheckpointError::Optimizer {
+                expected: Opt::NAME.to_string(),
+                found: self.optimizer.clone(),
+            });
+        }
+
+        Opt::check_state(&self.optimizer_state, param_count)
+    }
+}
+
+#[cfg(test)]
+mod checkpoint_tests {
+    use std::ops::{Add, Mul};
+
+    use crate::trainer::dyn_trainer::{dyn_param_translator, DynTrainer};
+    use crate::trainer::error::CheckpointError;
+    use crate::trainer::optimizer::adam::Adam;
+    use crate::trainer::optimizer::sgd::Sgd;
+    use crate::trainer::DataPoint;
+
+    fn linear<N: Clone + Add<N, Output = N> + Mul<f32, Output = N>>(
+        params: &[N],
+        input: &[f32; 1],
+        _: &(),
+    ) -> [N; 1] {
+        [params[0].clone() * input[0] + params[1].clone()]
+    }
+
+    fn file_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("ia_engine_{name}_{}.bin", std::process::id()))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn resumes_training() {
+        let dataset: Vec<DataPoint<1, 1>> = (-10..10)
+            .map(|x| x as f32 / 10.)
+            .map(|x| DataPoint::new([x], [2. * x + 1.]))
+            .collect();
+        let path = file_path("resume");
+
+        let new_trainer = || {
+            DynTrainer::new_reverse(2, linear, linear, dyn_param_translator, ())
+                .with_optimizer(Adam::default())
+                .with_model_id("line")
+        };
+
+        let mut original = new_trainer();
+        for _ in 0..10 {
+            original
+                .train_step::<false, false, _, _>(&dataset, &dataset)
+                .unwrap();
+        }
+        original.save(&path).unwrap();
+
+        let mut resumed = new_trainer();
+        resumed.load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(resumed.checkpoint(), original.checkpoint());
+        assert_eq!(resumed.get_step_count(), 10);
+
+        original
+            .train_step::<false, false, _, _>(&dataset, &dataset)
+            .unwrap();
+        resumed
+            .train_step::<false, false, _, _>(&dataset, &dataset)
+            .unwrap();
+
+        assert_eq!(resumed.get_model_params(), original.get_model_params());
+    }
+
+    #[test]
+    fn refuses_mismatched_checkpoints() {
+        let path = file_path("mismatch");
+
+        DynTrainer::new_reverse(2, linear, linear, dyn_param_translator, ())
+            .with_optimizer(Sgd::new(0.1))
+            .with_model_id("line")
+            .save(&path)
+            .unwrap();
+
+        let mut other_size = DynTrainer::new_reverse(3, linear, linear, dyn_param_translator, ())
+            .with_optimizer(Sgd::new(0.1))
+            .with_model_id("line");
+        let og_params = other_size.get_model_params();
+        assert!(matches!(
+            other_size.load(&path),
+            Err(CheckpointError::ParamCount {
+                expected: 3,
+                found: 2
+            })
+        ));
+        assert_eq!(other_size.get_model_params(), og_params);
+
+        let mut other_model = DynTrainer::new_reverse(2, linear, linear, dyn_param_translator, ())
+            .with_optimizer(Sgd::new(0.1))
+            .with_model_id("plane");
+        assert!(matches!(
+            other_model.load(&path),
+            Err(CheckpointError::ModelId { .. })
+        ));
+
+        let mut other_optimizer =
+            DynTrainer::new_reverse(2, linear, linear, dyn_param_translator, ())
+                .with_optimizer(Adam::default())
+                .with_model_id("line");
+        assert!(matches!(
+            other_optimizer.load(&path),
+            Err(CheckpointError::Optimizer { .. })
+        ));
+
+        std::fs::write(&path, "0.5\n0.25\n").unwrap();
+        assert!(matches!(
+            other_optimizer.load(&path),
+            Err(CheckpointError::Format(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn refuses_malformed_optimizer_states() {
+        let dataset = vec![DataPoint::new([1.], [3.])];
+        let mut trainer = DynTrainer::new_reverse(2, linear, linear, dyn_param_translator, ())
+            .with_optimizer(Adam::default());
+        trainer
+            .train_step::<false, false, _, _>(&dataset, &dataset)
+            .unwrap();
+
+        let mut short_moment = trainer.checkpoint();
+        short_moment.optimizer_state.buffers[1].pop();
+        let mut missing_moment = trainer.checkpoint();
+        missing_moment.optimizer_state.buffers.pop();
+
+        for mut checkpoint in [short_moment, missing_moment] {
+            checkpoint.params = vec![5., 5.];
+            let mut restored = DynTrainer::new_reverse(2, linear, linear, dyn_param_translator, ())
+                .with_optimizer(Adam::default());
+            let og_checkpoint = restored.checkpoint();
+
+            assert!(matches!(
+                restored.restore(checkpoint),
+                Err(CheckpointError::OptimizerState { param_count: 2, .. })
+            ));
+            assert_eq!(restored.checkpoint(), og_checkpoint);
+        }
+    }
+
+    #[test]
+    fn missing_file_is_not_created() {
+        let path = file_path("missing");
+
+        let mut trainer = DynTrainer::new_reverse(2, linear, linear, dyn_param_translator, ());
+
+        assert!(matches!(
+            trainer.load(&path),
+            Err(CheckpointError::Format(_))
+        ));
+        assert!(!std::path::Path::new(&path).exists());
+    }
+}
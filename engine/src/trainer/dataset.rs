@@ -0,0 +1,441 @@
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::ops::Range;
+
+use rand::seq::index;
+use rand_chacha::ChaCha8Rng;
+
+use crate::scalar::Scalar;
+use crate::trainer::error::TrainError;
+use crate::trainer::DataPoint;
+
+// What the trainers evaluate costs on. Data points are fetched by index so they can be borrowed
+// from memory as well as built on demand or read from disk, `get` is called from the rayon threads
+// and may be called more than once per index and step.
+pub trait Dataset<const I: usize, const O: usize, T: Scalar = f32>: Sync {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // index < len, datasets that can fail to fetch a data point (ie: read from disk) return the
+    // error and the trainers report it as a TrainError
+    fn get(&self, index: usize) -> io::Result<Cow<'_, DataPoint<I, O, T>>>;
+
+    // the data points in `range` as a dataset of its own, its index 0 is range.start
+    fn range(&self, range: Range<usize>) -> DatasetRange<'_, Self> {
+        assert!(range.end <= self.len(), "range out of the dataset");
+        DatasetRange {
+            dataset: self,
+            range,
+        }
+    }
+}
+
+impl<const I: usize, const O: usize, T: Scalar> Dataset<I, O, T> for [DataPoint<I, O, T>] {
+    fn len(&self) -> usize {
+        <[_]>::len(self)
+    }
+
+    fn get(&self, index: usize) -> io::Result<Cow<'_, DataPoint<I, O, T>>> {
+        Ok(Cow::Borrowed(&self[index]))
+    }
+}
+
+impl<const I: usize, const O: usize, T: Scalar> Dataset<I, O, T> for Vec<DataPoint<I, O, T>> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn get(&self, index: usize) -> io::Result<Cow<'_, DataPoint<I, O, T>>> {
+        Ok(Cow::Borrowed(&self[index]))
+    }
+}
+
+impl<const I: usize, const O: usize, T: Scalar, D: Dataset<I, O, T> + ?Sized> Dataset<I, O, T>
+    for &D
+{
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn get(&self, index: usize) -> io::Result<Cow<'_, DataPoint<I, O, T>>> {
+        (**self).get(index)
+    }
+}
+
+pub struct DatasetRange<'a, D: ?Sized> {
+    dataset: &'a D,
+    range: Range<usize>,
+}
+
+impl<const I: usize, const O: usize, T: Scalar, D: Dataset<I, O, T> + ?Sized> Dataset<I, O, T>
+    for DatasetRange<'_, D>
+{
+    fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    fn get(&self, index: usize) -> io::Result<Cow<'_, DataPoint<I, O, T>>> {
+        assert!(index < self.len(), "index out of the range");
+        self.dataset.get(self.range.start + index)
+    }
+}
+
+// Data points built from their index on every get, ie: samples of a known function. Only the
+// data points of the current step are ever in memory.
+pub struct Generated<F> {
+    len: usize,
+    generate: F,
+}
+
+impl<F> Generated<F> {
+    pub fn new(len: usize, generate: F) -> Self {
+        Self { len, generate }
+    }
+}
+
+impl<const I: usize, const O: usize, T: Scalar, F: Fn(usize) -> DataPoint<I, O, T> + Sync>
+    Dataset<I, O, T> for Generated<F>
+{
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, index: usize) -> io::Result<Cow<'_, DataPoint<I, O, T>>> {
+        assert!(index < self.len, "index out of the dataset");
+        Ok(Cow::Owned((self.generate)(index)))
+    }
+}
+
+// A dataset read from disk one data point at a time. The file is a sequence of fixed size
+// records: the inputs, the outputs and the weight as little endian f64s. Records are read at
+// their offset, so the rayon threads read in parallel without sharing a cursor.
+pub struct RecordFile<const I: usize, const O: usize> {
+    file: File,
+    len: usize,
+}
+
+impl<const I: usize, const O: usize> RecordFile<I, O> {
+    const RECORD_SIZE: usize = (I + O + 1) * size_of::<f64>();
+
+    pub fn create<T: Scalar, D: Dataset<I, O, T> + ?Sized>(
+        file_path: &str,
+        dataset: &D,
+    ) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(file_path)?);
+        for i in 0..dataset.len() {
+            let data_point = dataset.get(i)?;
+            let values = data_point
+                .input
+                .iter()
+                .chain(&data_point.output)
+                .chain([&data_point.weight]);
+            for value in values {
+                writer.write_all(&(*value).into().to_le_bytes())?;
+            }
+        }
+        writer.flush()
+    }
+
+    pub fn open(file_path: &str) -> io::Result<Self> {
+        let file = File::open(file_path)?;
+        let size = file.metadata()?.len() as usize;
+        if !size.is_multiple_of(Self::RECORD_SIZE) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{file_path} is not made of records of {I} inputs and {O} outputs"),
+            ));
+        }
+
+        Ok(Self {
+            file,
+            len: size / Self::RECORD_SIZE,
+        })
+    }
+}
+
+impl<const I: usize, const O: usize, T: Scalar> Dataset<I, O, T> for RecordFile<I, O> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, index: usize) -> io::Result<Cow<'_, DataPoint<I, O, T>>> {
+        assert!(index < self.len, "index out of the dataset");
+
+        let mut record = vec![0; Self::RECORD_SIZE];
+        read_exact_at(&self.file, &mut record, (index * Self::RECORD_SIZE) as u64)?;
+
+        let mut values = record
+            .chunks_exact(size_of::<f64>())
+            .map(|bytes| T::from_f64(f64::from_le_bytes(bytes.try_into().unwrap())));
+        let input = std::array::from_fn(|_| values.next().unwrap());
+        let output = std::array::from_fn(|_| values.next().unwrap());
+        let weight = values.next().unwrap();
+
+        Ok(Cow::Owned(
+            DataPoint::new(input, output).with_weight(weight),
+        ))
+    }
+}
+
+#[cfg(unix)]
+fn read_exact_at(file: &File, buffer: &mut [u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buffer, offset)
+}
+
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buffer: &mut [u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+
+    while !buffer.is_empty() {
+        match file.seek_read(buffer, offset)? {
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            read => {
+                buffer = &mut buffer[read..];
+                offset += read as u64;
+            }
+        }
+    }
+    Ok(())
+}
+
+// batch_size distinct random indices of the dataset (all of them if it is smaller) along with
+// their data points. The line search evaluates a batch several times, holding it in memory
+// keeps generated and disk datasets from being built or read again on every evaluation.
+pub(crate) fn minibatch<const I: usize, const O: usize, T: Scalar, D: Dataset<I, O, T> + ?Sized>(
+    dataset: &D,
+    batch_size: usize,
+    rng: &mut ChaCha8Rng,
+) -> Result<(Vec<usize>, Vec<DataPoint<I, O, T>>), TrainError> {
+    let indices = index::sample(rng, dataset.len(), batch_size.min(dataset.len())).into_vec();
+    let batch = indices
+        .iter()
+        .map(|i| {
+            dataset
+                .get(*i)
+                .map(Cow::into_owned)
+                .map_err(|err| TrainError::dataset(*i, err))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((indices, batch))
+}
+
+#[cfg(test)]
+mod dataset_tests {
+    use std::borrow::Cow;
+    use std::io;
+    use std::ops::{Add, Mul};
+
+    use super::{Dataset, Generated, RecordFile};
+    use crate::trainer::error::TrainError;
+    use crate::trainer::optimizer::adam::{Adam, AdamConfig};
+    use crate::trainer::optimizer::sgd::Sgd;
+    use crate::trainer::{default_param_translator, CriticalityCue, DataPoint, Trainer};
+
+    // y = 2x + 1 built on demand, nothing is stored
+    struct GeneratedLine(usize);
+
+    impl Dataset<1, 1> for GeneratedLine {
+        fn len(&self) -> usize {
+            self.0
+        }
+
+        fn get(&self, index: usize) -> io::Result<Cow<'_, DataPoint<1, 1>>> {
+            let x = index as f32 / self.0 as f32;
+            Ok(Cow::Owned(DataPoint::new([x], [2. * x + 1.])))
+        }
+    }
+
+    fn linear<T: Copy, N: Clone + Add<N, Output = N> + Mul<T, Output = N>>(
+        params: &[N; 2],
+        input: &[T; 1],
+        _: &(),
+    ) -> [N; 1] {
+        [params[0].clone() * input[0] + params[1].clone()]
+    }
+
+    #[test]
+    fn ranges() {
+        let dataset = GeneratedLine(10);
+        let range = dataset.range(4..7);
+
+        assert_eq!(range.len(), 3);
+        assert_eq!(range.get(0).unwrap().input, dataset.get(4).unwrap().input);
+        assert_eq!(
+            range.range(1..3).get(1).unwrap().input,
+            dataset.get(6).unwrap().input
+        );
+    }
+
+    #[test]
+    fn generated_trains_like_stored() {
+        let generated = GeneratedLine(20);
+        let stored: Vec<_> = (0..generated.len())
+            .map(|i| generated.get(i).unwrap().into_owned())
+            .collect();
+
+        let new_trainer = || {
+            Trainer::new_hybrid(
+                CriticalityCue::<2>(),
+                linear,
+                linear,
+                default_param_translator,
+                (),
+            )
+            .with_optimizer(Sgd::new(0.1))
+        };
+        let mut from_generated = new_trainer();
+        let mut from_stored = new_trainer();
+
+        for _ in 0..5 {
+            from_generated
+                .train_stocastic_step::<true, false, _, _>(&generated, 6, |_, _| {})
+                .unwrap();
+            from_stored
+                .train_stocastic_step::<true, false, _, _>(&stored, 6, |_, _| {})
+                .unwrap();
+        }
+
+        assert_eq!(
+            from_generated.get_model_params(),
+            from_stored.get_model_params()
+        );
+        assert_eq!(from_generated.get_last_cost(), from_stored.get_last_cost());
+    }
+
+    #[test]
+    fn record_file_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("ia_engine_records_{}.bin", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        let dataset = Generated::new(7, |i| {
+            DataPoint::new([i as f32, -(i as f32)], [0.1 * i as f32]).with_weight(i as f32)
+        });
+
+        RecordFile::create(&path, &dataset).unwrap();
+        let records = RecordFile::<2, 1>::open(&path).unwrap();
+        assert_eq!(Dataset::<2, 1>::len(&records), 7);
+        for i in 0..7 {
+            let record: Cow<DataPoint<2, 1>> = records.get(i).unwrap();
+            let data_point = dataset.get(i).unwrap();
+            assert_eq!(record.input, data_point.input);
+            assert_eq!(record.output, data_point.output);
+            assert_eq!(record.weight, data_point.weight);
+        }
+
+        assert!(RecordFile::<3, 1>::open(&path).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn unreadable_records_are_an_error() {
+        let path = std::env::temp_dir()
+            .join(format!("ia_engine_truncated_{}.bin", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        let dataset = Generated::new(10, |i| DataPoint::new([i as f32], [2. * i as f32]));
+        RecordFile::create(&path, &dataset).unwrap();
+        let records = RecordFile::<1, 1>::open(&path).unwrap();
+
+        // the last 4 records disappear after the file was checked
+        let record_size = RecordFile::<1, 1>::RECORD_SIZE as u64;
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(6 * record_size)
+            .unwrap();
+
+        let mut trainer = Trainer::new_hybrid(
+            CriticalityCue::<2>(),
+            linear,
+            linear,
+            default_param_translator,
+            (),
+        );
+        let og_params = trainer.get_model_params();
+
+        let err = trainer
+            .train_step::<true, false, _, _>(&records, &records)
+            .unwrap_err();
+        assert!(
+            matches!(err, TrainError::Dataset { data_point: 6, .. }),
+            "{err:?}"
+        );
+        let err = trainer
+            .train_minibatch_step::<true, false, _>(&records, 10)
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                TrainError::Dataset {
+                    data_point: 6..,
+                    ..
+                }
+            ),
+            "{err:?}"
+        );
+        assert_eq!(trainer.get_model_params(), og_params);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn minibatches_fit_a_generated_line() {
+        // far more data points than ever get evaluated
+        let dataset = Generated::new(1_000_000, |i| {
+            let x = i as f32 / 500_000. - 1.;
+            DataPoint::new([x], [2. * x + 1.])
+        });
+
+        let mut trainer = Trainer::new_hybrid(
+            CriticalityCue::<2>(),
+            linear,
+            linear,
+            default_param_translator,
+            (),
+        )
+        .with_optimizer(Adam::new(AdamConfig {
+            learning_rate: 0.01,
+            ..Default::default()
+        }));
+
+        for _ in 0..2000 {
+            trainer
+                .train_minibatch_step::<false, false, _>(&dataset, 16)
+                .unwrap();
+        }
+
+        let [slope, intercept] = trainer.get_model_params();
+        assert!((slope - 2.).abs() < 0.05, "slope {slope}");
+        assert!((intercept - 1.).abs() < 0.05, "intercept {intercept}");
+    }
+
+    #[test]
+    fn minibatch_errors_point_at_the_dataset() {
+        let dataset = Generated::new(50, |i| {
+            let goal = if i == 37 { f32::NAN } else { 0. };
+            DataPoint::new([i as f32], [goal])
+        });
+
+        let mut trainer = Trainer::new_hybrid(
+            CriticalityCue::<2>(),
+            linear,
+            linear,
+            default_param_translator,
+            (),
+        );
+
+        let err = trainer
+            .train_minibatch_step::<false, false, _>(&dataset, 100)
+            .unwrap_err();
+        assert!(
+            matches!(err, TrainError::NonFiniteCost { data_point: 37, .. }),
+            "{err:?}"
+        );
+    }
+}
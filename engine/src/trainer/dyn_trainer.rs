@@ -0,0 +1,466 @@
+use std::sync::Arc;
+
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+use crate::differentiable::Differentiable;
+use crate::dyn_dual::DynDual;
+use crate::reverse::Reverse;
+use crate::trainer::checkpoint::Checkpoint;
+use crate::trainer::dataset::{minibatch, Dataset};
+use crate::trainer::early_stopping::{EarlyStopping, EpochOutcome};
+use crate::trainer::error::{CheckpointError, TrainError};
+use crate::trainer::gradient::{GradientClipping, StepReport};
+use crate::trainer::initializer::Initializer;
+use crate::trainer::loss::Loss;
+use crate::trainer::optimizer::asintotic_search::AsintoticSearch;
+use crate::trainer::optimizer::Optimizer;
+use crate::trainer::regularization::Regularization;
+
+use super::{StepOutcome, TrainerBase};
+
+pub fn dyn_param_translator(params: &[f32], vector: &[f32]) -> Vec<f32> {
+    params.iter().zip(vector).map(|(p, v)| p + v).collect()
+}
+
+pub fn dyn_param_translator_with_bounds<const MAX: isize, const MIN: isize>(
+    params: &[f32],
+    vector: &[f32],
+) -> Vec<f32> {
+    params
+        .iter()
+        .zip(vector)
+        .map(|(p, v)| (p + v).min(MAX as f32).max(MIN as f32))
+        .collect()
+}
+
+// Same as Trainer but the parameter count is given at construction instead of being part of the
+// type, so the model size can come from configuration. The model gets its params as a slice of
+// `param_count` elements. DynDual and Reverse only come in f32, and so does DynTrainer.
+#[derive(Clone)]
+pub struct DynTrainer<
+    const I: usize,
+    const O: usize,
+    ExtraData: Sync + Clone,
+    N: Differentiable<Scalar = f32>,
+    FG: Fn(&[N], &[f32; I], &ExtraData) -> [N; O] + Sync + Clone,
+    F: Fn(&[f32], &[f32; I], &ExtraData) -> [f32; O] + Sync + Clone,
+    ParamTranslate: Fn(&[f32], &[f32]) -> Vec<f32> + Clone,
+    Opt: Optimizer,
+> {
+    model_gradient: FG,
+    model: F,
+    params: Vec<N>,
+    param_translator: ParamTranslate,
+    base: TrainerBase<f32, O, ExtraData, Opt>,
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        ExtraData: Sync + Clone,
+        N: Differentiable<Scalar = f32>,
+        FG: Fn(&[N], &[f32; I], &ExtraData) -> [N; O] + Sync + Clone,
+        F: Fn(&[f32], &[f32; I], &ExtraData) -> [f32; O] + Sync + Clone,
+        ParamTranslate: Fn(&[f32], &[f32]) -> Vec<f32> + Clone,
+    > DynTrainer<I, O, ExtraData, N, FG, F, ParamTranslate, AsintoticSearch>
+{
+    pub fn new(
+        param_count: usize,
+        trainable: F,
+        trainable_gradient: FG,
+        param_translator: ParamTranslate,
+        extra_data: ExtraData,
+    ) -> Self {
+        let mut base = TrainerBase::new(extra_data);
+
+        Self {
+            model_gradient: trainable_gradient,
+            model: trainable,
+            params: (0..param_count).map(|i| base.random_param(i)).collect(),
+            param_translator,
+            base,
+        }
+    }
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        ExtraData: Sync + Clone,
+        FG: Fn(&[DynDual], &[f32; I], &ExtraData) -> [DynDual; O] + Sync + Clone,
+        F: Fn(&[f32], &[f32; I], &ExtraData) -> [f32; O] + Sync + Clone,
+        ParamTranslate: Fn(&[f32], &[f32]) -> Vec<f32> + Clone,
+    > DynTrainer<I, O, ExtraData, DynDual, FG, F, ParamTranslate, AsintoticSearch>
+{
+    // the gradients are runtime sized, P doesn't need to be known at compile time
+    pub fn new_dense(
+        param_count: usize,
+        trainable: F,
+        trainable_gradient: FG,
+        param_translator: ParamTranslate,
+        extra_data: ExtraData,
+    ) -> Self {
+        Self::new(
+            param_count,
+            trainable,
+            trainable_gradient,
+            param_translator,
+            extra_data,
+        )
+    }
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        ExtraData: Sync + Clone,
+        FG: Fn(&[Reverse], &[f32; I], &ExtraData) -> [Reverse; O] + Sync + Clone,
+        F: Fn(&[f32], &[f32; I], &ExtraData) -> [f32; O] + Sync + Clone,
+        ParamTranslate: Fn(&[f32], &[f32]) -> Vec<f32> + Clone,
+    > DynTrainer<I, O, ExtraData, Reverse, FG, F, ParamTranslate, AsintoticSearch>
+{
+    pub fn new_reverse(
+        param_count: usize,
+        trainable: F,
+        trainable_gradient: FG,
+        param_translator: ParamTranslate,
+        extra_data: ExtraData,
+    ) -> Self {
+        Self::new(
+            param_count,
+            trainable,
+            trainable_gradient,
+            param_translator,
+            extra_data,
+        )
+    }
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        ExtraData: Sync + Clone,
+        N: Differentiable<Scalar = f32>,
+        FG: Fn(&[N], &[f32; I], &ExtraData) -> [N; O] + Sync + Clone,
+        F: Fn(&[f32], &[f32; I], &ExtraData) -> [f32; O] + Sync + Clone,
+        ParamTranslate: Fn(&[f32], &[f32]) -> Vec<f32> + Sync + Clone,
+        Opt: Optimizer,
+    > DynTrainer<I, O, ExtraData, N, FG, F, ParamTranslate, Opt>
+{
+    pub fn with_optimizer<NewOpt: Optimizer>(
+        self,
+        optimizer: NewOpt,
+    ) -> DynTrainer<I, O, ExtraData, N, FG, F, ParamTranslate, NewOpt> {
+        DynTrainer {
+            model_gradient: self.model_gradient,
+            model: self.model,
+            params: self.params,
+            param_translator: self.param_translator,
+            base: self.base.with_optimizer(optimizer),
+        }
+    }
+
+    pub fn with_loss(mut self, loss: Loss) -> Self {
+        self.base.loss = loss;
+        self
+    }
+
+    // multiplies the loss of each output, on top of the weights of the data points
+    pub fn with_output_weights(mut self, output_weights: [f32; O]) -> Self {
+        self.base.output_weights = Some(output_weights);
+        self
+    }
+
+    // stored in checkpoints, loading a checkpoint of another model fails
+    pub fn with_model_id(mut self, model_id: &str) -> Self {
+        self.base.model_id = model_id.to_string();
+        self
+    }
+
+    pub fn with_early_stopping(mut self, early_stopping: EarlyStopping) -> Self {
+        self.base.early_stopping = early_stopping;
+        self
+    }
+
+    pub fn with_gradient_clipping(mut self, gradient_clipping: GradientClipping) -> Self {
+        self.base.gradient_clipping = gradient_clipping;
+        self
+    }
+
+    pub fn with_regularization(mut self, regularization: Regularization) -> Self {
+        regularization.check_len(self.params.len());
+        self.base.regularization = regularization;
+        self
+    }
+
+    // reinitializes the params, two trainers with the same seed and the same calls end up with
+    // bit-identical params
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.base.seed = seed;
+        self.base.initialize_params(&mut self.params);
+        self
+    }
+
+    // reinitializes the params, with_seed and with_initializer can come in any order
+    pub fn with_initializer(mut self, initializer: Initializer) -> Self {
+        self.base.initializer = initializer;
+        self.base.initialize_params(&mut self.params);
+        self
+    }
+
+    // the dataset costs run on this pool instead of the global one, trainers can share it
+    pub fn with_thread_pool(mut self, thread_pool: Arc<ThreadPool>) -> Self {
+        self.base.thread_pool = Some(thread_pool);
+        self
+    }
+
+    // same as with_thread_pool with a pool of its own
+    pub fn with_threads(self, threads: usize) -> Result<Self, ThreadPoolBuildError> {
+        let thread_pool = ThreadPoolBuilder::new().num_threads(threads).build()?;
+        Ok(self.with_thread_pool(Arc::new(thread_pool)))
+    }
+
+    pub fn get_early_stopping(&self) -> &EarlyStopping {
+        &self.base.early_stopping
+    }
+
+    pub fn get_optimizer(&self) -> &Opt {
+        &self.base.optimizer
+    }
+
+    // amount of train steps that updated the params
+    pub fn get_step_count(&self) -> u64 {
+        self.base.step_count
+    }
+
+    pub fn param_count(&self) -> usize {
+        self.params.len()
+    }
+
+    pub fn get_model_params(&self) -> Vec<f32> {
+        self.params.iter().map(|p| p.get_real()).collect()
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.base.checkpoint(&self.params)
+    }
+
+    // the trainer is left untouched when the checkpoint doesn't match it
+    pub fn restore(&mut self, checkpoint: Checkpoint) -> Result<(), CheckpointError> {
+        self.base.restore(&mut self.params, checkpoint)
+    }
+
+    pub fn save(&self, file_path: &str) -> Result<(), CheckpointError> {
+        self.checkpoint().save(file_path)
+    }
+
+    pub fn load(&mut self, file_path: &str) -> Result<(), CheckpointError> {
+        self.restore(Checkpoint::load(file_path)?)
+    }
+
+    pub fn shake(&mut self, factor: f32) {
+        self.base.shake(&mut self.params, factor)
+    }
+
+    // the optimizer state is kept, last_cost is dropped as it belongs to the old params
+    pub fn set_params(&mut self, params: &[f32]) {
+        self.base.set_params(&mut self.params, params)
+    }
+
+    pub fn train_stocastic_step<
+        const PARALELIZE: bool,
+        const VERBOSE: bool,
+        D: Dataset<I, O> + ?Sized,
+        CB: Fn(usize, &mut Self),
+    >(
+        &mut self,
+        dataset: &D,
+        subdataset_size: usize,
+        inter_step_callback: CB,
+    ) -> Result<StepOutcome, TrainError> {
+        let mut ret = StepOutcome::Stalled;
+        for (i, start) in (0..dataset.len()).step_by(subdataset_size).enumerate() {
+            let sub_dataset = dataset.range(start..(start + subdataset_size).min(dataset.len()));
+            self.base.last_cost = None;
+            let report = self
+                .train_step::<PARALELIZE, VERBOSE, _, _>(&sub_dataset, dataset)
+                .map_err(|err| err.offset_data_point(start))?;
+
+            if report.outcome == StepOutcome::Stepped {
+                ret = StepOutcome::Stepped;
+            }
+            inter_step_callback(i, self);
+        }
+
+        Ok(ret)
+    }
+
+    // one train_step on batch_size data points sampled from the dataset, the line search runs on
+    // the same batch. Only the batch is held in memory so the dataset can be generated or read
+    // from disk as it goes.
+    pub fn train_minibatch_step<
+        const PARALELIZE: bool,
+        const VERBOSE: bool,
+        D: Dataset<I, O> + ?Sized,
+    >(
+        &mut self,
+        dataset: &D,
+        batch_size: usize,
+    ) -> Result<StepReport<f32>, TrainError> {
+        let (indices, batch) = minibatch(dataset, batch_size, &mut self.base.rng)?;
+        // every batch has a different cost, it can't be compared with the last one
+        self.base.last_cost = None;
+        self.train_step::<PARALELIZE, VERBOSE, _, _>(&batch, &batch)
+            .map_err(|err| err.map_data_point(|i| indices[i]))
+    }
+
+    // one train_stocastic_step followed by the validation of the resulting params. Once the
+    // early stopping patience runs out the params are reset to the best validated ones.
+    pub fn train_epoch<
+        const PARALELIZE: bool,
+        const VERBOSE: bool,
+        D: Dataset<I, O> + ?Sized,
+        V: Dataset<I, O> + ?Sized,
+        CB: Fn(usize, &mut Self),
+    >(
+        &mut self,
+        dataset: &D,
+        validation: &V,
+        subdataset_size: usize,
+        inter_step_callback: CB,
+    ) -> Result<EpochOutcome, TrainError> {
+        self.train_stocastic_step::<PARALELIZE, VERBOSE, _, _>(
+            dataset,
+            subdataset_size,
+            inter_step_callback,
+        )?;
+
+        self.base.validate::<PARALELIZE, VERBOSE, _, _, _>(
+            &mut self.params,
+            &self.model,
+            validation,
+        )
+    }
+
+    // the gradient comes from dir_dataset, the line search evaluates full_dataset
+    pub fn train_step<
+        const PARALELIZE: bool,
+        const VERBOSE: bool,
+        D: Dataset<I, O> + ?Sized,
+        E: Dataset<I, O> + ?Sized,
+    >(
+        &mut self,
+        dir_dataset: &D,
+        full_dataset: &E,
+    ) -> Result<StepReport<f32>, TrainError> {
+        self.base.train_step::<PARALELIZE, VERBOSE, _, _, _, _>(
+            &mut self.params,
+            &self.model_gradient,
+            &self.model,
+            &self.param_translator,
+            dir_dataset,
+            full_dataset,
+        )
+    }
+
+    pub fn get_last_cost(&self) -> Option<f32> {
+        self.base.last_cost
+    }
+
+    // report of the last train step, train_stocastic_step callbacks get the one of their chunk
+    pub fn get_last_report(&self) -> Option<&StepReport<f32>> {
+        self.base.last_report.as_ref()
+    }
+
+    pub fn eval(&self, input: &[f32; I]) -> [f32; O] {
+        (self.model)(&self.get_model_params(), input, &self.base.extra_data)
+    }
+}
+
+#[cfg(test)]
+mod dyn_trainer_tests {
+    use std::ops::{Add, Mul};
+
+    use super::{dyn_param_translator, DynTrainer};
+    use crate::trainer::optimizer::sgd::Sgd;
+    use crate::trainer::{default_param_translator, CriticalityCue, DataPoint, Trainer};
+
+    // polynomial whose degree is the param count
+    fn polynomial<N: Clone + Add<N, Output = N> + Mul<f32, Output = N>>(
+        params: &[N],
+        input: &[f32; 1],
+        _: &(),
+    ) -> [N; 1] {
+        let mut ret = params[0].clone();
+        for (i, p) in params.iter().enumerate().skip(1) {
+            ret = ret + p.clone() * input[0].powi(i as i32);
+        }
+        [ret]
+    }
+
+    fn dataset() -> Vec<DataPoint<1, 1>> {
+        (-10..10)
+            .map(|x| x as f32 / 10.)
+            .map(|x| DataPoint::new([x], [x * x - 2. * x + 1.]))
+            .collect()
+    }
+
+    #[test]
+    fn matches_static_trainer() {
+        let dataset = dataset();
+
+        let mut fixed = Trainer::new_hybrid(
+            CriticalityCue::<3>(),
+            |params: &[f32; 3], input: &[f32; 1], extra: &()| polynomial(params, input, extra),
+            |params: &[_; 3], input: &[f32; 1], extra: &()| polynomial(params, input, extra),
+            default_param_translator,
+            (),
+        )
+        .with_optimizer(Sgd::new(0.1));
+
+        let mut dense = DynTrainer::new_dense(3, polynomial, polynomial, dyn_param_translator, ())
+            .with_optimizer(Sgd::new(0.1));
+        let mut reverse =
+            DynTrainer::new_reverse(3, polynomial, polynomial, dyn_param_translator, ())
+                .with_optimizer(Sgd::new(0.1));
+
+        for _ in 0..20 {
+            fixed
+                .train_step::<false, false, _, _>(&dataset, &dataset)
+                .unwrap();
+            dense
+                .train_step::<false, false, _, _>(&dataset, &dataset)
+                .unwrap();
+            reverse
+                .train_step::<true, false, _, _>(&dataset, &dataset)
+                .unwrap();
+
+            let expected = fixed.get_model_params();
+            for params in [dense.get_model_params(), reverse.get_model_params()] {
+                assert_eq!(params.len(), 3);
+                for (e, p) in expected.iter().zip(params) {
+                    assert!((e - p).abs() < 1e-5, "{e} vs {p}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn seed_matches_static_trainer() {
+        let fixed = Trainer::new_hybrid(
+            CriticalityCue::<3>(),
+            |params: &[f32; 3], input: &[f32; 1], extra: &()| polynomial(params, input, extra),
+            |params: &[_; 3], input: &[f32; 1], extra: &()| polynomial(params, input, extra),
+            default_param_translator,
+            (),
+        )
+        .with_seed(5);
+        let reverse = DynTrainer::new_reverse(3, polynomial, polynomial, dyn_param_translator, ())
+            .with_seed(5);
+
+        assert_eq!(
+            fixed.get_model_params().to_vec(),
+            reverse.get_model_params()
+        );
+    }
+}
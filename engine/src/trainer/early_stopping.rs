@@ -0,0 +1,249 @@
+use crate::scalar::Scalar;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EpochOutcome {
+    // the validation cost is the best so far, the current params are kept as the best ones
+    Improved {
+        validation_cost: f64,
+    },
+    // no improvement for `stale_epochs` epochs in a row
+    Stale {
+        validation_cost: f64,
+        stale_epochs: usize,
+    },
+    // the patience ran out, the params were reset to the best ones. None when no validation cost
+    // was ever finite, the params are left as they are
+    Stopped {
+        best_cost: Option<f64>,
+    },
+}
+
+// Tracks the validation cost across epochs and remembers the params of the best one. Costs and
+// params are kept in f64 whatever the scalar of the trainer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EarlyStopping {
+    // epochs without improvement tolerated before stopping
+    pub patience: usize,
+    // improvements smaller than this don't count as such
+    pub min_delta: f64,
+    best_cost: Option<f64>,
+    best_params: Vec<f64>,
+    stale_epochs: usize,
+}
+
+impl EarlyStopping {
+    pub fn new(patience: usize) -> Self {
+        Self {
+            patience,
+            min_delta: 0.,
+            best_cost: None,
+            best_params: vec![],
+            stale_epochs: 0,
+        }
+    }
+
+    pub fn with_min_delta(mut self, min_delta: f64) -> Self {
+        self.min_delta = min_delta;
+        self
+    }
+
+    pub fn best_cost(&self) -> Option<f64> {
+        self.best_cost
+    }
+
+    pub fn best_params(&self) -> &[f64] {
+        &self.best_params
+    }
+
+    // a non finite validation cost never improves
+    pub(crate) fn update<T: Scalar>(&mut self, validation_cost: T, params: &[T]) -> EpochOutcome {
+        let validation_cost: f64 = validation_cost.into();
+        let improved = validation_cost.is_finite()
+            && self
+                .best_cost
+                .map(|best| validation_cost < best - self.min_delta)
+                .unwrap_or(true);
+
+        if improved {
+            self.best_cost = Some(validation_cost);
+            self.best_params = params.iter().map(|x| (*x).into()).collect();
+            self.stale_epochs = 0;
+
+            return EpochOutcome::Improved { validation_cost };
+        }
+
+        self.stale_epochs += 1;
+
+        if self.stale_epochs > self.patience {
+            self.stale_epochs = 0;
+            return EpochOutcome::Stopped {
+                best_cost: self.best_cost,
+            };
+        }
+
+        EpochOutcome::Stale {
+            validation_cost,
+            stale_epochs: self.stale_epochs,
+        }
+    }
+}
+
+impl Default for EarlyStopping {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+#[cfg(test)]
+mod early_stopping_tests {
+    use std::ops::{Add, Mul};
+
+    use super::{EarlyStopping, EpochOutcome};
+    use crate::trainer::optimizer::sgd::Sgd;
+    use crate::trainer::{default_param_translator, CriticalityCue, DataPoint, Trainer};
+
+    #[test]
+    fn patience() {
+        let mut early_stopping = EarlyStopping::new(2).with_min_delta(0.1);
+
+        assert_eq!(
+            early_stopping.update(1f32, &[1.]),
+            EpochOutcome::Improved {
+                validation_cost: 1.
+            }
+        );
+        assert_eq!(
+            early_stopping.update(0.95f64, &[2.]),
+            EpochOutcome::Stale {
+                validation_cost: 0.95,
+                stale_epochs: 1
+            }
+        );
+        assert!(matches!(
+            early_stopping.update(f64::NAN, &[3.]),
+            EpochOutcome::Stale {
+                stale_epochs: 2,
+                ..
+            }
+        ));
+        assert_eq!(
+            early_stopping.update(2f32, &[4.]),
+            EpochOutcome::Stopped {
+                best_cost: Some(1.)
+            }
+        );
+        assert_eq!(early_stopping.best_params(), &[1.]);
+    }
+
+    #[test]
+    fn stops_without_a_finite_cost() {
+        let mut early_stopping = EarlyStopping::new(2);
+
+        for stale_epochs in 1..=2 {
+            assert!(matches!(
+                early_stopping.update(f32::NAN, &[1.]),
+                EpochOutcome::Stale { stale_epochs: x, .. } if x == stale_epochs
+            ));
+        }
+        assert_eq!(
+            early_stopping.update(f32::INFINITY, &[1.]),
+            EpochOutcome::Stopped { best_cost: None }
+        );
+        assert!(early_stopping.best_params().is_empty());
+    }
+
+    fn linear<N: Clone + Add<N, Output = N> + Mul<f32, Output = N>>(
+        params: &[N; 2],
+        input: &[f32; 1],
+        _: &(),
+    ) -> [N; 1] {
+        [params[0].clone() * input[0] + params[1].clone()]
+    }
+
+    fn line_dataset(slope: f32) -> Vec<DataPoint<1, 1>> {
+        (-10..10)
+            .map(|x| x as f32 / 10.)
+            .map(|x| DataPoint::new([x], [slope * x + 1.]))
+            .collect()
+    }
+
+    #[test]
+    fn stops_at_the_best_params() {
+        // the params go through the validation optimum on their way to the training one
+        let dataset = line_dataset(2.);
+        let validation = line_dataset(1.);
+
+        let mut trainer = Trainer::new_hybrid(
+            CriticalityCue::<2>(),
+            linear,
+            linear,
+            default_param_translator,
+            (),
+        )
+        .with_optimizer(Sgd::new(0.05))
+        .with_early_stopping(EarlyStopping::new(3));
+
+        let mut epochs = 0;
+        let best_cost = loop {
+            epochs += 1;
+            assert!(epochs < 1000, "never stopped");
+
+            let outcome = trainer
+                .train_epoch::<false, false, _, _, _>(&dataset, &validation, 5, |_, _| {})
+                .unwrap();
+
+            if let EpochOutcome::Stopped {
+                best_cost: Some(best_cost),
+            } = outcome
+            {
+                break best_cost;
+            }
+        };
+
+        let params = trainer.get_model_params();
+        assert_eq!(
+            trainer.get_early_stopping().best_params(),
+            &params.map(f64::from)
+        );
+
+        let [slope, _] = params;
+        assert!((slope - 1.).abs() < 0.2, "slope {slope}");
+
+        let validation_cost = validation
+            .iter()
+            .map(|point| (linear(&params, &point.input, &())[0] - point.output[0]).abs())
+            .sum::<f32>()
+            / validation.len() as f32;
+        assert!((f64::from(validation_cost) - best_cost).abs() < 1e-5);
+    }
+
+    #[test]
+    fn stops_on_a_nan_validation_set() {
+        let dataset = line_dataset(2.);
+        let validation: Vec<_> = line_dataset(1.)
+            .iter()
+            .map(|point| DataPoint::new(point.input, [f32::NAN]))
+            .collect();
+
+        let mut trainer = Trainer::new_hybrid(
+            CriticalityCue::<2>(),
+            linear,
+            linear,
+            default_param_translator,
+            (),
+        )
+        .with_optimizer(Sgd::new(0.05))
+        .with_early_stopping(EarlyStopping::new(3));
+
+        let outcomes: Vec<_> = (0..4)
+            .map(|_| {
+                trainer
+                    .train_epoch::<false, false, _, _, _>(&dataset, &validation, 5, |_, _| {})
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(outcomes[3], EpochOutcome::Stopped { best_cost: None });
+        assert!(trainer.get_model_params().iter().all(|p| p.is_finite()));
+    }
+}
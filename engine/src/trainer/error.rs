@@ -0,0 +1,167 @@
+use std::{error::Error, fmt::Display};
+
+use savefile::SavefileError;
+
+use crate::scalar::Scalar;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrainError {
+    // a data point evaluated to NaN or infinity. `parameters` lists the gradient entries that are
+    // not finite, it is empty when only the cost itself is
+    NonFiniteCost {
+        data_point: usize,
+        parameters: Vec<usize>,
+    },
+    // the optimizer step moved these parameters to NaN or infinity, the step was not applied
+    NonFiniteParameters {
+        parameters: Vec<usize>,
+    },
+    // the dataset is empty or all its weights are 0, it has no cost to average
+    NoWeight,
+    // the data point weight is negative, NaN or infinite
+    InvalidWeight {
+        data_point: usize,
+    },
+    // the dataset failed to fetch a data point, ie: a record file that can't be read anymore
+    Dataset {
+        data_point: usize,
+        message: String,
+    },
+}
+
+impl Display for TrainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrainError::NonFiniteCost {
+                data_point,
+                parameters,
+            } => write!(
+                f,
+                "data point {data_point} has a non finite cost (non finite gradient at parameters {parameters:?})"
+            ),
+            TrainError::NonFiniteParameters { parameters } => {
+                write!(f, "the step produced non finite parameters {parameters:?}")
+            }
+            TrainError::NoWeight => write!(f, "the dataset has no data points with weight"),
+            TrainError::InvalidWeight { data_point } => write!(
+                f,
+                "data point {data_point} has a negative or non finite weight"
+            ),
+            TrainError::Dataset {
+                data_point,
+                message,
+            } => write!(f, "data point {data_point} could not be fetched: {message}"),
+        }
+    }
+}
+
+impl Error for TrainError {}
+
+impl TrainError {
+    pub(crate) fn dataset(data_point: usize, err: std::io::Error) -> Self {
+        TrainError::Dataset {
+            data_point,
+            message: err.to_string(),
+        }
+    }
+
+    // data point indices are relative to the dataset that was evaluated, shift them when it was
+    // a chunk of a bigger one
+    pub(crate) fn offset_data_point(self, offset: usize) -> Self {
+        self.map_data_point(|data_point| data_point + offset)
+    }
+
+    // same for a sample of a bigger one, `index` takes the position in the sample to the one in
+    // the bigger dataset
+    pub(crate) fn map_data_point(self, index: impl FnOnce(usize) -> usize) -> Self {
+        match self {
+            TrainError::NonFiniteCost {
+                data_point,
+                parameters,
+            } => TrainError::NonFiniteCost {
+                data_point: index(data_point),
+                parameters,
+            },
+            TrainError::InvalidWeight { data_point } => TrainError::InvalidWeight {
+                data_point: index(data_point),
+            },
+            TrainError::Dataset {
+                data_point,
+                message,
+            } => TrainError::Dataset {
+                data_point: index(data_point),
+                message,
+            },
+            err => err,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    // the file could not be read or written, or it is not a checkpoint this version understands
+    Format(SavefileError),
+    // the checkpoint was written by a trainer with a different amount of params
+    ParamCount {
+        expected: usize,
+        found: usize,
+    },
+    ModelId {
+        expected: String,
+        found: String,
+    },
+    Optimizer {
+        expected: String,
+        found: String,
+    },
+    // the optimizer state has the wrong amount of buffers or their length isn't the param count
+    OptimizerState {
+        optimizer: String,
+        param_count: usize,
+    },
+}
+
+impl Display for CheckpointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckpointError::Format(err) => write!(f, "invalid checkpoint: {err}"),
+            CheckpointError::ParamCount { expected, found } => write!(
+                f,
+                "the checkpoint has {found} parameters but the model has {expected}"
+            ),
+            CheckpointError::ModelId { expected, found } => write!(
+                f,
+                "the checkpoint belongs to model {found:?} instead of {expected:?}"
+            ),
+            CheckpointError::Optimizer { expected, found } => write!(
+                f,
+                "the checkpoint was trained with {found} but the trainer uses {expected}"
+            ),
+            CheckpointError::OptimizerState {
+                optimizer,
+                param_count,
+            } => write!(
+                f,
+                "the {optimizer} state in the checkpoint doesn't fit {param_count} parameters"
+            ),
+        }
+    }
+}
+
+impl Error for CheckpointError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CheckpointError::Format(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn non_finite_indices<T: Scalar>(values: &[T]) -> Vec<usize> {
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, x)| !x.is_finite())
+        .map(|(i, _)| i)
+        .collect()
+}
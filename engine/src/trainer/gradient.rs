@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use crate::scalar::Scalar;
+use crate::trainer::StepOutcome;
+
+// Applied to the gradient before the optimizer sees it, value clipping goes first. The line search
+// normalizes the gradient, so only value clipping changes its steps.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GradientClipping {
+    // clamps every entry to [-max_value, max_value]
+    pub max_value: Option<f32>,
+    // scales the whole gradient down when its euclidean norm goes over max_norm
+    pub max_norm: Option<f32>,
+}
+
+impl GradientClipping {
+    // true if the gradient changed
+    pub(crate) fn clip<T: Scalar>(&self, gradient: &mut [T]) -> bool {
+        let mut clipped = false;
+
+        if let Some(max_value) = self.max_value {
+            let max_value = T::from(max_value);
+            for entry in gradient.iter_mut() {
+                let clamped = entry.max(-max_value).min(max_value);
+                clipped |= clamped != *entry;
+                *entry = clamped;
+            }
+        }
+
+        if let Some(max_norm) = self.max_norm {
+            let max_norm = T::from(max_norm);
+            let norm = norm(gradient);
+            if norm > max_norm {
+                T::scale_slice(gradient, max_norm / norm);
+                clipped = true;
+            }
+        }
+
+        clipped
+    }
+}
+
+pub(crate) fn norm<T: Scalar>(gradient: &[T]) -> T {
+    gradient.iter().map(|g| *g * *g).sum::<T>().sqrt()
+}
+
+// What a train step did. The gradient stats are the ones of the data cost, before the
+// regularization and the clipping, zero_gradients counts the params no data point of the step
+// depends on (ie: dead relu neurons). The cost includes the regularization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepReport<T = f32> {
+    pub outcome: StepOutcome,
+    // the cost stored as last cost
+    pub cost: T,
+    pub gradient_norm: T,
+    // (param index, gradient entry)
+    pub max_gradient: (usize, T),
+    pub min_gradient: (usize, T),
+    pub zero_gradients: usize,
+    pub clipped: bool,
+    pub duration: Duration,
+}
+
+impl<T: Scalar> StepReport<T> {
+    // stats of an unclipped gradient, the outcome and the cost are filled once the step is done
+    pub(crate) fn new(gradient: &[T]) -> Self {
+        let mut max_gradient = (0, T::ZERO);
+        let mut min_gradient = (0, T::ZERO);
+        for (i, entry) in gradient.iter().enumerate() {
+            if i == 0 || *entry > max_gradient.1 {
+                max_gradient = (i, *entry);
+            }
+            if i == 0 || *entry < min_gradient.1 {
+                min_gradient = (i, *entry);
+            }
+        }
+
+        Self {
+            outcome: StepOutcome::Stalled,
+            cost: T::ZERO,
+            gradient_norm: norm(gradient),
+            max_gradient,
+            min_gradient,
+            zero_gradients: gradient.iter().filter(|g| **g == T::ZERO).count(),
+            clipped: false,
+            duration: Duration::ZERO,
+        }
+    }
+}
+
+#[cfg(test)]
+mod gradient_tests {
+    use super::{GradientClipping, StepReport};
+
+    #[test]
+    fn clipping() {
+        let mut gradient = [3., -4., 0.];
+        assert!(!GradientClipping::default().clip(&mut gradient));
+        assert_eq!(gradient, [3., -4., 0.]);
+
+        let by_value = GradientClipping {
+            max_value: Some(2.),
+            ..Default::default()
+        };
+        assert!(by_value.clip(&mut gradient));
+        assert_eq!(gradient, [2., -2., 0.]);
+
+        let mut gradient: [f32; 3] = [3., -4., 0.];
+        let by_norm = GradientClipping {
+            max_norm: Some(1.),
+            ..Default::default()
+        };
+        assert!(by_norm.clip(&mut gradient));
+        for (entry, expected) in gradient.into_iter().zip([0.6, -0.8, 0.]) {
+            assert!((entry - expected).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn report() {
+        let report = StepReport::new(&[3., -4., 0., 0.]);
+
+        assert_eq!(report.gradient_norm, 5.);
+        assert_eq!(report.max_gradient, (0, 3.));
+        assert_eq!(report.min_gradient, (1, -4.));
+        assert_eq!(report.zero_gradients, 2);
+    }
+}
@@ -0,0 +1,167 @@
+use std::f64::consts::PI;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use rand_chacha::ChaCha8Rng;
+
+use crate::scalar::Scalar;
+
+pub type ParamInitializer<T = f32> = dyn Fn(usize, &mut ChaCha8Rng) -> T + Send + Sync;
+
+// How the trainers set the initial value of each param, in the scalar of the model. Xavier and He
+// give every param the same fans, so they only fit models made of a single layer. Networks whose
+// layers differ have to draw each param with the fans of its layer through PerParam.
+#[derive(Clone)]
+pub enum Initializer<T = f32> {
+    Constant(T),
+    // in [min, max)
+    Uniform { min: T, max: T },
+    Normal { mean: T, std_dev: T },
+    // Xavier/Glorot uniform, for sigmoid and tanh layers
+    Xavier { fan_in: usize, fan_out: usize },
+    // He normal, for relu layers
+    He { fan_in: usize },
+    // takes the param index, draws from the rng of the trainer to stay reproducible
+    PerParam(Arc<ParamInitializer<T>>),
+}
+
+impl<T: Scalar> Initializer<T> {
+    pub fn sample(&self, index: usize, rng: &mut ChaCha8Rng) -> T {
+        match self {
+            Initializer::Constant(value) => *value,
+            Initializer::Uniform { min, max } => *min + (*max - *min) * T::random(rng),
+            Initializer::Normal { mean, std_dev } => *mean + *std_dev * standard_normal(rng),
+            Initializer::Xavier { fan_in, fan_out } => {
+                let limit = (T::from(6.) / T::from((fan_in + fan_out) as f32)).sqrt();
+                limit * (T::from(2.) * T::random(rng) - T::ONE)
+            }
+            Initializer::He { fan_in } => {
+                (T::from(2.) / T::from(*fan_in as f32)).sqrt() * standard_normal(rng)
+            }
+            Initializer::PerParam(initializer) => initializer(index, rng),
+        }
+    }
+}
+
+// the same init the trainers always had
+impl<T: Scalar> Default for Initializer<T> {
+    fn default() -> Self {
+        Initializer::Uniform {
+            min: T::from(-0.5),
+            max: T::from(0.5),
+        }
+    }
+}
+
+impl<T: Debug> Debug for Initializer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Initializer::Constant(value) => f.debug_tuple("Constant").field(value).finish(),
+            Initializer::Uniform { min, max } => f
+                .debug_struct("Uniform")
+                .field("min", min)
+                .field("max", max)
+                .finish(),
+            Initializer::Normal { mean, std_dev } => f
+                .debug_struct("Normal")
+                .field("mean", mean)
+                .field("std_dev", std_dev)
+                .finish(),
+            Initializer::Xavier { fan_in, fan_out } => f
+                .debug_struct("Xavier")
+                .field("fan_in", fan_in)
+                .field("fan_out", fan_out)
+                .finish(),
+            Initializer::He { fan_in } => f.debug_struct("He").field("fan_in", fan_in).finish(),
+            Initializer::PerParam(_) => f.write_str("PerParam"),
+        }
+    }
+}
+
+// Box-Muller, 1 - random keeps ln away from 0
+fn standard_normal<T: Scalar>(rng: &mut ChaCha8Rng) -> T {
+    let radius = (T::from(-2.) * (T::ONE - T::random(rng)).ln()).sqrt();
+    radius * (T::from(2.) * T::from_f64(PI) * T::random(rng)).cos()
+}
+
+#[cfg(test)]
+mod initializer_tests {
+    use std::sync::Arc;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::Initializer;
+
+    fn moments(initializer: &Initializer) -> (f32, f32) {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        let samples: Vec<f32> = (0..100_000)
+            .map(|i| initializer.sample(i, &mut rng))
+            .collect();
+
+        let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+        let variance =
+            samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f32>() / samples.len() as f32;
+
+        (mean, variance.sqrt())
+    }
+
+    #[test]
+    fn distributions() {
+        let cases = [
+            (Initializer::Constant(0.3), 0.3, 0.),
+            (
+                Initializer::Uniform { min: 0., max: 1. },
+                0.5,
+                (1f32 / 12.).sqrt(),
+            ),
+            (
+                Initializer::Normal {
+                    mean: 1.,
+                    std_dev: 2.,
+                },
+                1.,
+                2.,
+            ),
+            // the variance of Xavier is 2 / (fan_in + fan_out)
+            (
+                Initializer::Xavier {
+                    fan_in: 30,
+                    fan_out: 10,
+                },
+                0.,
+                (2f32 / 40.).sqrt(),
+            ),
+            (Initializer::He { fan_in: 50 }, 0., (2f32 / 50.).sqrt()),
+        ];
+
+        for (initializer, mean, std_dev) in cases {
+            let (sample_mean, sample_std_dev) = moments(&initializer);
+            assert!(
+                (sample_mean - mean).abs() < 0.02,
+                "{initializer:?} mean {sample_mean}"
+            );
+            assert!(
+                (sample_std_dev - std_dev).abs() < 0.02,
+                "{initializer:?} std dev {sample_std_dev}"
+            );
+        }
+    }
+
+    #[test]
+    fn f64_keeps_its_precision() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+        assert_eq!(Initializer::Constant(0.1f64).sample(0, &mut rng), 0.1);
+        let uniform = Initializer::<f64>::default().sample(0, &mut rng);
+        assert_ne!(f64::from(uniform as f32), uniform);
+    }
+
+    #[test]
+    fn per_param() {
+        let initializer = Initializer::PerParam(Arc::new(|i, _| i as f32));
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+
+        assert_eq!(initializer.sample(3, &mut rng), 3.);
+    }
+}
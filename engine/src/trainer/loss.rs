@@ -0,0 +1,164 @@
+use std::ops::{Add, Mul, Sub};
+
+use crate::dual::extended_arithmetic::ExtendedArithmetic;
+use crate::scalar::Scalar;
+
+// keeps ln away from 0 when a prediction saturates
+const CROSS_ENTROPY_EPSILON: f32 = 1e-7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Loss {
+    // sum of absolute residuals
+    #[default]
+    L1,
+    // sum of squared residuals, averaged over the dataset this is the MSE
+    L2,
+    // quadratic for residuals smaller than delta, linear beyond it
+    Huber {
+        delta: f32,
+    },
+    // expects the prediction to be a probability distribution (ie: a softmax output)
+    CrossEntropy,
+}
+
+impl Loss {
+    pub fn cost<
+        const O: usize,
+        T: Scalar,
+        N: ExtendedArithmetic
+            + Clone
+            + From<f32>
+            + PartialOrd<T>
+            + Add<N, Output = N>
+            + Add<T, Output = N>
+            + Sub<T, Output = N>
+            + Mul<N, Output = N>
+            + Mul<T, Output = N>,
+    >(
+        &self,
+        prediction: [N; O],
+        goal: &[T; O],
+    ) -> N {
+        self.weighted_cost(prediction, goal, None)
+    }
+
+    // the cost of each output is multiplied by its weight (ie: class weights of a classifier)
+    pub fn weighted_cost<
+        const O: usize,
+        T: Scalar,
+        N: ExtendedArithmetic
+            + Clone
+            + From<f32>
+            + PartialOrd<T>
+            + Add<N, Output = N>
+            + Add<T, Output = N>
+            + Sub<T, Output = N>
+            + Mul<N, Output = N>
+            + Mul<T, Output = N>,
+    >(
+        &self,
+        prediction: [N; O],
+        goal: &[T; O],
+        weights: Option<&[T; O]>,
+    ) -> N {
+        let mut ret = N::from(0.);
+
+        for (i, (pred_val, goal_val)) in prediction.into_iter().zip(goal).enumerate() {
+            let cost = match self {
+                Loss::L1 => (pred_val - *goal_val).abs(),
+                Loss::L2 => {
+                    let residual = pred_val - *goal_val;
+                    residual.clone() * residual
+                }
+                Loss::Huber { delta } => {
+                    let delta = T::from(*delta);
+                    let half = T::from(0.5);
+                    let residual = (pred_val - *goal_val).abs();
+                    if residual <= delta {
+                        residual.clone() * residual * half
+                    } else {
+                        (residual - delta * half) * delta
+                    }
+                }
+                Loss::CrossEntropy => {
+                    if *goal_val == T::ZERO {
+                        continue;
+                    }
+                    (pred_val + T::from(CROSS_ENTROPY_EPSILON)).ln() * -*goal_val
+                }
+            };
+
+            ret = match weights {
+                Some(weights) => ret + cost * weights[i],
+                None => ret + cost,
+            };
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod loss_tests {
+    use crate::{dual::Dual, simd_arr::dense_simd::DenseSimd};
+
+    use super::Loss;
+
+    const LOSSES: [Loss; 4] = [
+        Loss::L1,
+        Loss::L2,
+        Loss::Huber { delta: 0.5 },
+        Loss::CrossEntropy,
+    ];
+
+    #[test]
+    fn known_values() {
+        let prediction = [0.25, 0.75];
+        let goal: [f32; 2] = [0., 1.];
+
+        assert_eq!(Loss::L1.cost(prediction, &goal), 0.5);
+        assert_eq!(Loss::L2.cost(prediction, &goal), 0.125);
+        assert_eq!(Loss::Huber { delta: 0.5 }.cost(prediction, &goal), 0.0625);
+        assert!((Loss::Huber { delta: 0.1 }.cost(prediction, &goal) - 0.04).abs() < 1e-6);
+        assert!((Loss::CrossEntropy.cost(prediction, &goal) - 0.75f32.ln().abs()).abs() < 1e-5);
+        assert_eq!(
+            Loss::L2.weighted_cost(prediction, &goal, Some(&[2., 0.5])),
+            0.0625 * 2. + 0.0625 * 0.5
+        );
+    }
+
+    #[test]
+    fn perfect_prediction_is_free() {
+        let goal: [f32; 3] = [0., 1., 0.];
+
+        for loss in LOSSES {
+            assert!(loss.cost(goal, &goal).abs() < 1e-5, "{loss:?}");
+        }
+    }
+
+    #[test]
+    fn dual_matches_f32() {
+        let prediction = [0.1, 0.7, 0.2];
+        let goal: [f32; 3] = [0., 1., 0.];
+
+        for loss in LOSSES {
+            let dual_prediction: [Dual<3, DenseSimd<3>>; 3] =
+                std::array::from_fn(|i| Dual::new_param(prediction[i], i));
+
+            let scalar = loss.cost(prediction, &goal);
+            let dual = loss.cost(dual_prediction, &goal);
+
+            assert!((scalar - dual.get_real()).abs() < 1e-6, "{loss:?}");
+
+            for (i, derivative) in dual.get_gradient().into_iter().enumerate() {
+                let mut nudged = prediction;
+                nudged[i] += 1e-3;
+                let finite_difference = (loss.cost(nudged, &goal) - scalar) / 1e-3;
+
+                assert!(
+                    (finite_difference - derivative).abs() < 1e-2,
+                    "{loss:?} d/d{i}: {finite_difference} vs {derivative}"
+                );
+            }
+        }
+    }
+}
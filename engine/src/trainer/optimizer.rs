@@ -0,0 +1,236 @@
+pub mod adam;
+pub mod asintotic_search;
+pub mod momentum;
+pub mod rms_prop;
+pub mod schedule;
+pub mod sgd;
+
+use savefile_derive::Savefile;
+
+use crate::scalar::Scalar;
+use crate::trainer::error::CheckpointError;
+
+pub struct OptimizerStep<T = f32> {
+    pub displacement: Vec<T>,
+    // cost after applying the displacement, if the optimizer had to evaluate it
+    pub cost: Option<T>,
+}
+
+// internal state of an optimizer (ie: Adam moments) as stored in checkpoints. Kept in f64 so it
+// holds the state of f32 and f64 models alike
+#[derive(Savefile, Debug, Clone, PartialEq, Default)]
+pub struct OptimizerState {
+    pub buffers: Vec<Vec<f64>>,
+    pub step: u64,
+}
+
+pub trait Optimizer: Clone {
+    // identifies the optimizer in checkpoints, a checkpoint only loads into the same optimizer
+    const NAME: &'static str;
+
+    // `cost_fn` evaluates the full dataset cost of the params moved by a displacement
+    fn step<T: Scalar, C: FnMut(&[T]) -> T>(
+        &mut self,
+        params: &[T],
+        gradient: &[T],
+        cost_fn: C,
+    ) -> Option<OptimizerStep<T>>;
+
+    fn state(&self) -> OptimizerState {
+        OptimizerState::default()
+    }
+
+    // errors unless `state` could have been written by this optimizer training param_count params
+    fn check_state(state: &OptimizerState, param_count: usize) -> Result<(), CheckpointError> {
+        check_buffers::<Self>(state, 0, param_count)
+    }
+
+    // a state that doesn't pass check_state is refused and the optimizer is left untouched
+    fn set_state(
+        &mut self,
+        state: OptimizerState,
+        param_count: usize,
+    ) -> Result<(), CheckpointError> {
+        Self::check_state(&state, param_count)
+    }
+}
+
+// `count` per param buffers, all of them empty before the first step
+pub(crate) fn check_buffers<Opt: Optimizer>(
+    state: &OptimizerState,
+    count: usize,
+    param_count: usize,
+) -> Result<(), CheckpointError> {
+    let len = state.buffers.first().map(Vec::len).unwrap_or(0);
+    if state.buffers.len() != count
+        || (len != 0 && len != param_count)
+        || state.buffers.iter().any(|buffer| buffer.len() != len)
+    {
+        return Err(CheckpointError::OptimizerState {
+            optimizer: Opt::NAME.to_string(),
+            param_count,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod optimizer_tests {
+    use super::{
+        adam::Adam, asintotic_search::AsintoticSearch, momentum::Momentum, rms_prop::RmsProp,
+        schedule::LrSchedule, sgd::Sgd, Optimizer, OptimizerState,
+    };
+
+    const TARGET: [f32; 3] = [1., -2., 0.5];
+
+    fn cost(params: &[f32]) -> f32 {
+        params
+            .iter()
+            .zip(TARGET)
+            .map(|(p, t)| (p - t) * (p - t))
+            .sum()
+    }
+
+    fn gradient(params: &[f32]) -> Vec<f32> {
+        params
+            .iter()
+            .zip(TARGET)
+            .map(|(p, t)| 2. * (p - t))
+            .collect()
+    }
+
+    fn minimize<Opt: Optimizer>(optimizer: Opt, steps: usize) -> f32 {
+        minimize_counting(optimizer, steps, false).0
+    }
+
+    // also returns the amount of cost evaluations, `forget` starts every step from a fresh optimizer
+    fn minimize_counting<Opt: Optimizer>(
+        fresh_optimizer: Opt,
+        steps: usize,
+        forget: bool,
+    ) -> (f32, usize) {
+        let mut optimizer = fresh_optimizer.clone();
+        let mut params = vec![0.; 3];
+        let mut evaluations = 0;
+
+        for _ in 0..steps {
+            if forget {
+                optimizer = fresh_optimizer.clone();
+            }
+            let og_params = params.clone();
+            let step = optimizer.step(&params, &gradient(&params), |displacement| {
+                evaluations += 1;
+                let moved: Vec<f32> = og_params
+                    .iter()
+                    .zip(displacement)
+                    .map(|(p, d)| p + d)
+                    .collect();
+                cost(&moved)
+            });
+
+            if let Some(step) = step {
+                for (p, d) in params.iter_mut().zip(step.displacement) {
+                    *p += d;
+                }
+            }
+        }
+
+        (cost(&params), evaluations)
+    }
+
+    #[test]
+    fn asintotic_search_converges() {
+        assert!(minimize(AsintoticSearch::default(), 100) < 1e-4);
+    }
+
+    #[test]
+    fn asintotic_search_remembers_the_step_length() {
+        let (forgetful_cost, forgetful_evaluations) =
+            minimize_counting(AsintoticSearch::default(), 30, true);
+        let (cost, evaluations) = minimize_counting(AsintoticSearch::default(), 30, false);
+
+        assert!(cost <= forgetful_cost, "{cost} vs {forgetful_cost}");
+        assert!(
+            evaluations < forgetful_evaluations,
+            "{evaluations} vs {forgetful_evaluations}"
+        );
+    }
+
+    #[test]
+    fn asintotic_search_state_round_trip() {
+        let mut optimizer = AsintoticSearch::default();
+        optimizer.step(&[0.], &[1.], |d: &[f32]| (d[0] + 0.1) * (d[0] + 0.1));
+
+        let mut restored = AsintoticSearch::default();
+        restored.set_state(optimizer.state(), 1).unwrap();
+        assert_eq!(restored.get_step_length(), optimizer.get_step_length());
+        assert!(optimizer.get_step_length() < 1.);
+    }
+
+    #[test]
+    fn asintotic_search_rejects_invalid_step_lengths() {
+        for buffers in [
+            vec![],
+            vec![vec![f64::INFINITY]],
+            vec![vec![f64::NAN]],
+            vec![vec![0.]],
+        ] {
+            let state = OptimizerState { buffers, step: 0 };
+            assert!(AsintoticSearch::default().set_state(state, 1).is_err());
+        }
+    }
+
+    #[test]
+    fn sgd_converges() {
+        assert!(minimize(Sgd::new(0.1), 200) < 1e-4);
+    }
+
+    #[test]
+    fn scheduled_sgd_converges() {
+        let schedule = LrSchedule::Warmup {
+            steps: 10,
+            then: Box::new(LrSchedule::Cosine {
+                steps: 190,
+                min_learning_rate: 0.01,
+            }),
+        };
+        assert!(minimize(Sgd::new(0.1).with_schedule(schedule), 200) < 1e-4);
+    }
+
+    #[test]
+    fn momentum_converges() {
+        assert!(minimize(Momentum::new(0.05, 0.9), 500) < 1e-4);
+    }
+
+    #[test]
+    fn rms_prop_converges() {
+        assert!(minimize(RmsProp::new(0.01, 0.9, 1e-8), 1000) < 1e-3);
+    }
+
+    #[test]
+    fn adam_converges() {
+        assert!(minimize(Adam::default(), 5000) < 1e-4);
+    }
+
+    #[test]
+    fn adam_restores_steps_past_i32() {
+        let step = u64::from(u32::MAX) + 1;
+        let mut adam = Adam::default();
+        adam.set_state(
+            OptimizerState {
+                buffers: vec![vec![0.5], vec![0.25]],
+                step,
+            },
+            1,
+        )
+        .unwrap();
+
+        let displacement = adam
+            .step(&[0.], &[1.], |_: &[f32]| 0.)
+            .unwrap()
+            .displacement;
+        assert!(displacement[0].is_finite() && displacement[0] < 0.);
+        assert_eq!(adam.state().step, step + 1);
+    }
+}
@@ -0,0 +1,130 @@
+use crate::scalar::Scalar;
+use crate::trainer::error::CheckpointError;
+
+use super::{check_buffers, schedule::LrSchedule, Optimizer, OptimizerState, OptimizerStep};
+
+#[derive(Debug, Clone, Copy)]
+pub struct AdamConfig {
+    pub learning_rate: f32,
+    pub beta1: f32,
+    pub beta2: f32,
+    pub epsilon: f32,
+}
+
+impl Default for AdamConfig {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.001,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Adam {
+    pub config: AdamConfig,
+    pub schedule: LrSchedule,
+    first_moment: Vec<f64>,
+    second_moment: Vec<f64>,
+    step: u64,
+}
+
+impl Adam {
+    pub fn new(config: AdamConfig) -> Self {
+        Self {
+            config,
+            schedule: LrSchedule::Constant,
+            first_moment: vec![],
+            second_moment: vec![],
+            step: 0,
+        }
+    }
+
+    pub fn with_schedule(mut self, schedule: LrSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+}
+
+impl Default for Adam {
+    fn default() -> Self {
+        Self::new(AdamConfig::default())
+    }
+}
+
+impl Optimizer for Adam {
+    const NAME: &'static str = "adam";
+
+    fn step<T: Scalar, C: FnMut(&[T]) -> T>(
+        &mut self,
+        _: &[T],
+        gradient: &[T],
+        _: C,
+    ) -> Option<OptimizerStep<T>> {
+        let beta1 = f64::from(self.config.beta1);
+        let beta2 = f64::from(self.config.beta2);
+        let learning_rate = f64::from(
+            self.schedule
+                .learning_rate(self.config.learning_rate, self.step),
+        );
+        let epsilon = f64::from(self.config.epsilon);
+
+        self.first_moment.resize(gradient.len(), 0.);
+        self.second_moment.resize(gradient.len(), 0.);
+        self.step += 1;
+
+        // past i32::MAX both powers are 0 anyway
+        let exponent = i32::try_from(self.step).unwrap_or(i32::MAX);
+        let first_correction = 1. - beta1.powi(exponent);
+        let second_correction = 1. - beta2.powi(exponent);
+
+        let displacement = self
+            .first_moment
+            .iter_mut()
+            .zip(self.second_moment.iter_mut())
+            .zip(gradient)
+            .map(|((m, v), g)| {
+                let g: f64 = (*g).into();
+                *m = beta1 * *m + (1. - beta1) * g;
+                *v = beta2 * *v + (1. - beta2) * g * g;
+
+                let m_hat = *m / first_correction;
+                let v_hat = *v / second_correction;
+
+                T::from_f64(-learning_rate * m_hat / (v_hat.sqrt() + epsilon))
+            })
+            .collect();
+
+        Some(OptimizerStep {
+            displacement,
+            cost: None,
+        })
+    }
+
+    fn state(&self) -> OptimizerState {
+        OptimizerState {
+            buffers: vec![self.first_moment.clone(), self.second_moment.clone()],
+            step: self.step,
+        }
+    }
+
+    fn check_state(state: &OptimizerState, param_count: usize) -> Result<(), CheckpointError> {
+        check_buffers::<Self>(state, 2, param_count)
+    }
+
+    fn set_state(
+        &mut self,
+        state: OptimizerState,
+        param_count: usize,
+    ) -> Result<(), CheckpointError> {
+        Self::check_state(&state, param_count)?;
+        let [first_moment, second_moment] = <[Vec<f64>; 2]>::try_from(state.buffers).unwrap();
+
+        self.first_moment = first_moment;
+        self.second_moment = second_moment;
+        self.step = state.step;
+        Ok(())
+    }
+}
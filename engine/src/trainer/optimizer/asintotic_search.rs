@@ -0,0 +1,114 @@
+use crate::scalar::Scalar;
+use crate::trainer::error::CheckpointError;
+
+use super::{Optimizer, OptimizerState, OptimizerStep};
+
+// Backtracking line search along the normalized gradient. Each step starts from the length
+// accepted by the previous one times `growth` and shrinks it by `shrink` until the full dataset
+// cost improves, so it only pays a few cost evaluations once the length settles.
+#[derive(Clone, Debug)]
+pub struct AsintoticSearch {
+    pub growth: f32,
+    pub shrink: f32,
+    // first length tried by the next step
+    step_length: f64,
+}
+
+impl AsintoticSearch {
+    pub fn new(growth: f32, shrink: f32) -> Self {
+        Self {
+            growth,
+            shrink,
+            step_length: 1.,
+        }
+    }
+
+    pub fn get_step_length(&self) -> f64 {
+        self.step_length
+    }
+}
+
+impl Default for AsintoticSearch {
+    fn default() -> Self {
+        Self::new(2., 0.7)
+    }
+}
+
+impl Optimizer for AsintoticSearch {
+    const NAME: &'static str = "asintotic_search";
+
+    fn step<T: Scalar, C: FnMut(&[T]) -> T>(
+        &mut self,
+        _: &[T],
+        gradient: &[T],
+        mut cost_fn: C,
+    ) -> Option<OptimizerStep<T>> {
+        let gradient_size: T = gradient
+            .iter()
+            .fold(T::ZERO, |acc, elm| acc + (*elm * *elm))
+            .max(T::from(1e-30));
+
+        let unit_gradient: Vec<T> = gradient.iter().map(|g| *g / gradient_size.sqrt()).collect();
+
+        let og_cost = cost_fn(&vec![T::ZERO; gradient.len()]);
+
+        let mut factor = self.step_length;
+
+        loop {
+            let displacement: Vec<T> = unit_gradient
+                .iter()
+                .map(|e| -*e * T::from_f64(factor))
+                .collect();
+
+            let new_cost = cost_fn(&displacement);
+
+            if new_cost < og_cost {
+                self.step_length = factor * f64::from(self.growth);
+                return Some(OptimizerStep {
+                    displacement,
+                    cost: Some(new_cost),
+                });
+            }
+
+            factor *= f64::from(self.shrink);
+
+            if factor < 1e-10 {
+                // nothing to remember, the next step searches from scratch
+                self.step_length = 1.;
+                return None;
+            }
+        }
+    }
+
+    fn state(&self) -> OptimizerState {
+        OptimizerState {
+            buffers: vec![vec![self.step_length]],
+            step: 0,
+        }
+    }
+
+    // checkpoints written before the step length was remembered have no buffers
+    fn check_state(state: &OptimizerState, param_count: usize) -> Result<(), CheckpointError> {
+        match state.buffers.as_slice() {
+            [step_length]
+                if step_length.len() == 1 && step_length[0] > 0. && step_length[0].is_finite() =>
+            {
+                Ok(())
+            }
+            _ => Err(CheckpointError::OptimizerState {
+                optimizer: Self::NAME.to_string(),
+                param_count,
+            }),
+        }
+    }
+
+    fn set_state(
+        &mut self,
+        state: OptimizerState,
+        param_count: usize,
+    ) -> Result<(), CheckpointError> {
+        Self::check_state(&state, param_count)?;
+        self.step_length = state.buffers[0][0];
+        Ok(())
+    }
+}
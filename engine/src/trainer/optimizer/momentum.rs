@@ -0,0 +1,77 @@
+use crate::scalar::Scalar;
+use crate::trainer::error::CheckpointError;
+
+use super::{check_buffers, schedule::LrSchedule, Optimizer, OptimizerState, OptimizerStep};
+
+#[derive(Clone, Debug)]
+pub struct Momentum {
+    pub learning_rate: f32,
+    pub momentum: f32,
+    pub schedule: LrSchedule,
+    velocity: Vec<f64>,
+    step: u64,
+}
+
+impl Momentum {
+    pub fn new(learning_rate: f32, momentum: f32) -> Self {
+        Self {
+            learning_rate,
+            momentum,
+            schedule: LrSchedule::Constant,
+            velocity: vec![],
+            step: 0,
+        }
+    }
+
+    pub fn with_schedule(mut self, schedule: LrSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+}
+
+impl Optimizer for Momentum {
+    const NAME: &'static str = "momentum";
+
+    fn step<T: Scalar, C: FnMut(&[T]) -> T>(
+        &mut self,
+        _: &[T],
+        gradient: &[T],
+        _: C,
+    ) -> Option<OptimizerStep<T>> {
+        self.velocity.resize(gradient.len(), 0.);
+        let learning_rate = f64::from(self.schedule.learning_rate(self.learning_rate, self.step));
+        self.step += 1;
+
+        for (v, g) in self.velocity.iter_mut().zip(gradient) {
+            let g: f64 = (*g).into();
+            *v = f64::from(self.momentum) * *v - learning_rate * g;
+        }
+
+        Some(OptimizerStep {
+            displacement: self.velocity.iter().map(|v| T::from_f64(*v)).collect(),
+            cost: None,
+        })
+    }
+
+    fn state(&self) -> OptimizerState {
+        OptimizerState {
+            buffers: vec![self.velocity.clone()],
+            step: self.step,
+        }
+    }
+
+    fn check_state(state: &OptimizerState, param_count: usize) -> Result<(), CheckpointError> {
+        check_buffers::<Self>(state, 1, param_count)
+    }
+
+    fn set_state(
+        &mut self,
+        state: OptimizerState,
+        param_count: usize,
+    ) -> Result<(), CheckpointError> {
+        Self::check_state(&state, param_count)?;
+        self.velocity = state.buffers.into_iter().next().unwrap();
+        self.step = state.step;
+        Ok(())
+    }
+}
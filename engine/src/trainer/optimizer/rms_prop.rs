@@ -0,0 +1,87 @@
+use crate::scalar::Scalar;
+use crate::trainer::error::CheckpointError;
+
+use super::{check_buffers, schedule::LrSchedule, Optimizer, OptimizerState, OptimizerStep};
+
+#[derive(Clone, Debug)]
+pub struct RmsProp {
+    pub learning_rate: f32,
+    pub decay: f32,
+    pub epsilon: f32,
+    pub schedule: LrSchedule,
+    mean_square: Vec<f64>,
+    step: u64,
+}
+
+impl RmsProp {
+    pub fn new(learning_rate: f32, decay: f32, epsilon: f32) -> Self {
+        Self {
+            learning_rate,
+            decay,
+            epsilon,
+            schedule: LrSchedule::Constant,
+            mean_square: vec![],
+            step: 0,
+        }
+    }
+
+    pub fn with_schedule(mut self, schedule: LrSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+}
+
+impl Optimizer for RmsProp {
+    const NAME: &'static str = "rms_prop";
+
+    fn step<T: Scalar, C: FnMut(&[T]) -> T>(
+        &mut self,
+        _: &[T],
+        gradient: &[T],
+        _: C,
+    ) -> Option<OptimizerStep<T>> {
+        self.mean_square.resize(gradient.len(), 0.);
+        let decay = f64::from(self.decay);
+        let learning_rate = f64::from(self.schedule.learning_rate(self.learning_rate, self.step));
+        self.step += 1;
+        let epsilon = f64::from(self.epsilon);
+
+        let displacement = self
+            .mean_square
+            .iter_mut()
+            .zip(gradient)
+            .map(|(ms, g)| {
+                let g: f64 = (*g).into();
+                *ms = decay * *ms + (1. - decay) * g * g;
+                T::from_f64(-learning_rate * g / (ms.sqrt() + epsilon))
+            })
+            .collect();
+
+        Some(OptimizerStep {
+            displacement,
+            cost: None,
+        })
+    }
+
+    fn state(&self) -> OptimizerState {
+        OptimizerState {
+            buffers: vec![self.mean_square.clone()],
+            step: self.step,
+        }
+    }
+
+    fn check_state(state: &OptimizerState, param_count: usize) -> Result<(), CheckpointError> {
+        check_buffers::<Self>(state, 1, param_count)
+    }
+
+    fn set_state(
+        &mut self,
+        state: OptimizerState,
+        param_count: usize,
+    ) -> Result<(), CheckpointError> {
+        Self::check_state(&state, param_count)?;
+        self.mean_square = state.buffers.into_iter().next().unwrap();
+        self.step = state.step;
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use std::f32::consts::PI;
+
+// Learning rate of the gradient based optimizers as a function of the steps they already took
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum LrSchedule {
+    #[default]
+    Constant,
+    // multiplies the rate by gamma every `every` steps
+    StepDecay {
+        every: u64,
+        gamma: f32,
+    },
+    // multiplies the rate by gamma every step
+    Exponential {
+        gamma: f32,
+    },
+    // anneals the rate down to min_learning_rate along half a cosine over `steps`, then keeps it
+    Cosine {
+        steps: u64,
+        min_learning_rate: f32,
+    },
+    // ramps the rate up linearly over `steps`, then follows `then` from its own step 0
+    Warmup {
+        steps: u64,
+        then: Box<LrSchedule>,
+    },
+}
+
+impl LrSchedule {
+    pub fn learning_rate(&self, base: f32, step: u64) -> f32 {
+        match self {
+            LrSchedule::Constant => base,
+            LrSchedule::StepDecay { every, gamma } => {
+                base * gamma.powf((step / (*every).max(1)) as f32)
+            }
+            LrSchedule::Exponential { gamma } => base * gamma.powf(step as f32),
+            LrSchedule::Cosine {
+                steps,
+                min_learning_rate,
+            } => {
+                let progress = step.min(*steps) as f32 / (*steps).max(1) as f32;
+                min_learning_rate + (base - min_learning_rate) * 0.5 * (1. + (PI * progress).cos())
+            }
+            // the first step already moves, a zero rate would waste it
+            LrSchedule::Warmup { steps, .. } if step < *steps => {
+                base * (step + 1) as f32 / *steps as f32
+            }
+            LrSchedule::Warmup { steps, then } => then.learning_rate(base, step - steps),
+        }
+    }
+}
+
+#[cfg(test)]
+mod schedule_tests {
+    use super::LrSchedule;
+
+    fn rates(schedule: &LrSchedule, steps: u64) -> Vec<f32> {
+        (0..steps)
+            .map(|step| schedule.learning_rate(1., step))
+            .collect()
+    }
+
+    fn assert_close(rates: Vec<f32>, expected: &[f32]) {
+        assert_eq!(rates.len(), expected.len());
+        for (rate, expected) in rates.into_iter().zip(expected) {
+            assert!((rate - expected).abs() < 1e-6, "{rate} vs {expected}");
+        }
+    }
+
+    #[test]
+    fn learning_rates() {
+        assert_close(rates(&LrSchedule::Constant, 3), &[1., 1., 1.]);
+        assert_close(
+            rates(
+                &LrSchedule::StepDecay {
+                    every: 2,
+                    gamma: 0.5,
+                },
+                5,
+            ),
+            &[1., 1., 0.5, 0.5, 0.25],
+        );
+        assert_close(
+            rates(&LrSchedule::Exponential { gamma: 0.5 }, 3),
+            &[1., 0.5, 0.25],
+        );
+        assert_close(
+            rates(
+                &LrSchedule::Cosine {
+                    steps: 2,
+                    min_learning_rate: 0.2,
+                },
+                4,
+            ),
+            &[1., 0.6, 0.2, 0.2],
+        );
+        assert_close(
+            rates(
+                &LrSchedule::Warmup {
+                    steps: 4,
+                    then: Box::new(LrSchedule::Exponential { gamma: 0.5 }),
+                },
+                6,
+            ),
+            &[0.25, 0.5, 0.75, 1., 1., 0.5],
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use crate::scalar::Scalar;
+use crate::trainer::error::CheckpointError;
+
+use super::{schedule::LrSchedule, Optimizer, OptimizerState, OptimizerStep};
+
+#[derive(Clone, Debug)]
+pub struct Sgd {
+    pub learning_rate: f32,
+    pub schedule: LrSchedule,
+    step: u64,
+}
+
+impl Sgd {
+    pub fn new(learning_rate: f32) -> Self {
+        Self {
+            learning_rate,
+            schedule: LrSchedule::Constant,
+            step: 0,
+        }
+    }
+
+    pub fn with_schedule(mut self, schedule: LrSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+}
+
+impl Optimizer for Sgd {
+    const NAME: &'static str = "sgd";
+
+    fn step<T: Scalar, C: FnMut(&[T]) -> T>(
+        &mut self,
+        _: &[T],
+        gradient: &[T],
+        _: C,
+    ) -> Option<OptimizerStep<T>> {
+        let learning_rate = T::from(self.schedule.learning_rate(self.learning_rate, self.step));
+        self.step += 1;
+
+        Some(OptimizerStep {
+            displacement: gradient.iter().map(|g| -learning_rate * *g).collect(),
+            cost: None,
+        })
+    }
+
+    fn state(&self) -> OptimizerState {
+        OptimizerState {
+            buffers: vec![],
+            step: self.step,
+        }
+    }
+
+    fn set_state(
+        &mut self,
+        state: OptimizerState,
+        param_count: usize,
+    ) -> Result<(), CheckpointError> {
+        Self::check_state(&state, param_count)?;
+        self.step = state.step;
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use crate::scalar::Scalar;
+
+// Penalties on the magnitude of the params. L1 and L2 are added to the cost, to the one the
+// gradient is taken from and to the one the line search compares, so every optimizer sees them.
+// Their gradient is added analytically instead of running the penalty through the duals. The
+// weight decay is decoupled from the cost (AdamW style): every candidate step also shrinks the
+// params by weight_decay * param. Validation costs stay unregularized.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Regularization {
+    pub l1: f32,
+    pub l2: f32,
+    pub weight_decay: f32,
+    // one entry per param, false leaves the param out (ie: biases). None regularizes them all
+    pub mask: Option<Vec<bool>>,
+}
+
+impl Regularization {
+    fn applies(&self, i: usize) -> bool {
+        self.mask.as_ref().map(|mask| mask[i]).unwrap_or(true)
+    }
+
+    pub(crate) fn check_len(&self, param_count: usize) {
+        if let Some(mask) = &self.mask {
+            assert_eq!(
+                mask.len(),
+                param_count,
+                "the regularization mask needs an entry per param"
+            );
+        }
+    }
+
+    pub(crate) fn penalty<T: Scalar>(&self, params: &[T]) -> T {
+        if self.l1 == 0. && self.l2 == 0. {
+            return T::ZERO;
+        }
+
+        let (l1, l2) = (T::from(self.l1), T::from(self.l2));
+        params
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.applies(*i))
+            .map(|(_, p)| l1 * p.abs() + l2 * *p * *p)
+            .sum()
+    }
+
+    // the subgradient of |p| at 0 is taken as 0
+    pub(crate) fn add_gradient<T: Scalar>(&self, params: &[T], gradient: &mut [T]) {
+        if self.l1 == 0. && self.l2 == 0. {
+            return;
+        }
+
+        let (l1, l2) = (T::from(self.l1), T::from(self.l2));
+        for (i, (p, g)) in params.iter().zip(gradient).enumerate() {
+            if !self.applies(i) {
+                continue;
+            }
+
+            let sign = if *p > T::ZERO {
+                T::ONE
+            } else if *p < T::ZERO {
+                -T::ONE
+            } else {
+                T::ZERO
+            };
+            *g += l1 * sign + T::from(2.) * l2 * *p;
+        }
+    }
+
+    pub(crate) fn decay<T: Scalar>(&self, params: &mut [T]) {
+        if self.weight_decay == 0. {
+            return;
+        }
+
+        let factor = T::ONE - T::from(self.weight_decay);
+        for (i, p) in params.iter_mut().enumerate() {
+            if self.applies(i) {
+                *p *= factor;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod regularization_tests {
+    use super::Regularization;
+
+    #[test]
+    fn penalty_gradient_and_decay() {
+        let regularization = Regularization {
+            l1: 0.5,
+            l2: 2.,
+            weight_decay: 0.1,
+            mask: Some(vec![true, true, false]),
+        };
+        let mut params: [f32; 3] = [1., -2., 3.];
+
+        assert_eq!(regularization.penalty(&params), 0.5 * 3. + 2. * 5.);
+
+        let mut gradient = [0.; 3];
+        regularization.add_gradient(&params, &mut gradient);
+        assert_eq!(gradient, [0.5 + 4., -0.5 - 8., 0.]);
+
+        regularization.decay(&mut params);
+        assert_eq!(params, [0.9, -1.8, 3.]);
+    }
+}
@@ -1,8 +1,9 @@
 #![feature(generic_arg_infer)]
 
 use ia_engine::{
+    dual::Dual,
     simd_arr::dense_simd::DenseSimd,
-    trainer::{default_param_translator, DataPoint, Trainer},
+    trainer::{default_param_translator, DataPoint, StepOutcome, Trainer},
 };
 
 fn direct<N: Clone>(parameters: &[N; 1], _: &[f32; 0], _: &()) -> [N; 1] {
@@ -10,19 +11,21 @@ fn direct<N: Clone>(parameters: &[N; 1], _: &[f32; 0], _: &()) -> [N; 1] {
 }
 
 fn main() {
-    let dataset = vec![DataPoint {
-        input: [],
-        output: [-200.],
-    }];
+    let dataset = vec![DataPoint::new([], [-200.])];
 
-    let mut trainer: Trainer<_, _, _, _, DenseSimd<_>, _, _, _> = Trainer::new(
+    let mut trainer: Trainer<_, _, _, _, Dual<_, DenseSimd<_>>, _, _, _, _> = Trainer::new(
         direct,
         direct,
         default_param_translator,
         (),
     );
 
-    while trainer.train_step(&dataset) {
+    while trainer
+        .train_step::<false, false, _, _>(&dataset, &dataset)
+        .unwrap()
+        .outcome
+        == StepOutcome::Stepped
+    {
         println!("{:?}", trainer.get_model_params());
     }
     println!("{:?}", trainer.get_model_params());
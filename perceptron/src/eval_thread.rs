@@ -1,16 +1,20 @@
-use ia_engine::trainer::{default_param_translator, CriticalityCue, Trainer};
+use ia_engine::trainer::dyn_trainer::{dyn_param_translator, DynTrainer};
+use ia_engine::trainer::optimizer::adam::Adam;
 
-use crate::neuronal_network::neuronal_network;
+use crate::neuronal_network::{model_id, neuronal_network, parameter_count};
 
-pub fn eval_thread(pixel_input: &[f32; 14 * 14]) -> [f32; 10] {
-    let mut trainer = Trainer::new_hybrid(
-        CriticalityCue::<{ 6740 / 20 }>(),
-        neuronal_network::<{ 14 * 14 }, 10, 6740, _>,
-        neuronal_network::<{ 14 * 14 }, _, _, _>,
-        default_param_translator,
-        // param_translator_with_bounds::<_, 4, -4>,
-        vec![14 * 14, 30, 20, 10],
-    );
+pub fn eval_thread(pixel_input: &[f32; 14 * 14], structure: Vec<usize>) -> [f32; 10] {
+    let id = model_id(&structure);
+    let mut trainer = DynTrainer::new_reverse(
+        parameter_count(&structure),
+        neuronal_network::<{ 14 * 14 }, 10, _>,
+        neuronal_network::<{ 14 * 14 }, 10, _>,
+        dyn_param_translator,
+        // dyn_param_translator_with_bounds::<4, -4>,
+        structure,
+    )
+    .with_optimizer(Adam::default())
+    .with_model_id(&id);
 
 
     trainer.load("model.bin").unwrap();
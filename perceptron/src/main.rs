@@ -8,39 +8,56 @@ mod matrix;
 mod mnist;
 mod neuronal_network;
 
-use std::{env, thread, time::Instant};
+use std::{env, time::Instant};
 
 use crate::eval_thread::eval_thread;
-use ia_engine::trainer::{default_param_translator, CriticalityCue, Trainer};
+use ia_engine::metrics::{argmax, classification_report};
+use ia_engine::trainer::loss::Loss;
+use ia_engine::trainer::DataPoint;
+use ia_engine::trainer::optimizer::adam::Adam;
+use ia_engine::trainer::dyn_trainer::{dyn_param_translator, DynTrainer};
+use ia_engine::trainer::early_stopping::{EarlyStopping, EpochOutcome};
+use ia_engine::trainer::regularization::Regularization;
 
 use mnist::load_data;
-use neuronal_network::neuronal_network;
+use neuronal_network::{model_id, neuronal_network, parameter_count, regularization_mask};
 use piston_window::*;
 use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 use vecmath::*;
 
+// drives both the trainer and the shuffles of train, the same seed reproduces a run
+const SEED: u64 = 2;
+
+// layer sizes after the mode (ie: `train 196 30 20 10`), the input has to be 14 * 14 and the
+// output 10
+fn network_structure(args: &[String]) -> Vec<usize> {
+    if args.is_empty() {
+        return vec![14 * 14, 30, 20, 10];
+    }
+
+    args.iter()
+        .map(|layer| layer.parse().expect("layer sizes must be integers"))
+        .collect()
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
+    let structure = network_structure(&args[2..]);
 
     match args[1].as_str() {
-        "train" => thread::Builder::new()
-            .stack_size(20_000_000_000)
-            .name("big stack main".into())
-            .spawn(train_main)
-            .unwrap()
-            .join()
-            .unwrap(),
-        "demo" => main_demo(),
+        "train" => train_main(structure),
+        "demo" => main_demo(structure),
         x => unimplemented!("{}", x),
     }
 }
 
-fn main_demo() {
+fn main_demo(structure: Vec<usize>) {
     let mut last_change_time = None;
     let mut rng = rand::thread_rng();
 
-    let mut dataset: Vec<ia_engine::trainer::DataPoint<0, _, 10>> =
-        load_data("mnist/t10k").unwrap();
+    let mut dataset: Vec<ia_engine::trainer::DataPoint<_, 10>> = load_data("mnist/t10k").unwrap();
 
     let mut pixel_input = [0.; { 14 * 14 }];
 
@@ -149,55 +166,110 @@ fn main_demo() {
         if let Some(t) = last_change_time {
             if t.elapsed().as_secs_f64() > 0.5 {
                 last_change_time = None;
-                let predition = thread::Builder::new()
-                    .stack_size(20_000_000_000)
-                    .name("big stack main".into())
-                    .spawn(move || eval_thread(&pixel_input))
-                    .unwrap()
-                    .join()
-                    .unwrap();
-
-                let mut max_pos = 0;
-
-                for (i, pred) in predition.iter().enumerate() {
-                    if predition[max_pos] < *pred {
-                        max_pos = i;
-                    }
-                }
+                let predition = eval_thread(&pixel_input, structure.clone());
 
-                println!("predition: {max_pos}, {:?} ", predition)
+                println!("predition: {}, {:?} ", argmax(&predition), predition)
             }
         }
     }
 }
 
-fn train_main() {
-    let mut rng = rand::thread_rng();
+fn train_main(structure: Vec<usize>) {
+    let mut rng = ChaCha8Rng::seed_from_u64(SEED);
 
     // let mut dataset = load_data("mnist/t10k").unwrap();
     let mut dataset = load_data("mnist/train").unwrap();
 
-    let mut trainer = Trainer::new_hybrid(
-        CriticalityCue::<{ 6740 / 20 }>(),
-        neuronal_network::<{ 14 * 14 }, 10, 6740, _>,
-        neuronal_network::<{ 14 * 14 }, _, _, _>,
-        default_param_translator,
-        // param_translator_with_bounds::<_, 4, -4>,
-        vec![14 * 14, 30, 20, 10],
-    );
-
-    trainer.load("model.bin").unwrap();
+    let id = model_id(&structure);
+    let mask = regularization_mask(&structure);
+    let mut trainer = DynTrainer::new_reverse(
+        parameter_count(&structure),
+        neuronal_network::<{ 14 * 14 }, 10, _>,
+        neuronal_network::<{ 14 * 14 }, 10, _>,
+        dyn_param_translator,
+        // dyn_param_translator_with_bounds::<4, -4>,
+        structure,
+    )
+    .with_seed(SEED)
+    .with_optimizer(Adam::default())
+    .with_loss(Loss::CrossEntropy)
+    // keeps the weights small against overfitting, the biases are left free
+    .with_regularization(Regularization {
+        weight_decay: 1e-4,
+        mask: Some(mask),
+        ..Default::default()
+    })
+    .with_model_id(&id)
+    .with_early_stopping(EarlyStopping::new(5));
+
+    if let Err(err) = trainer.load("model.bin") {
+        eprintln!("training from scratch, model.bin not loaded: {err}");
+    }
 
     const SUBDATASET_SIZE: usize = 16 * 16 * 16;
+    const VALIDATION_SIZE: usize = 6000;
+
+    // held out of the train set, t10k stays untouched for the final evaluation
+    dataset.shuffle(&mut rng);
+    let validation = dataset.split_off(dataset.len() - VALIDATION_SIZE);
+    let dataset_len = dataset.len();
+    // a failed step leaves the params as they were, the same error would come back every epoch
+    let mut failed_epochs = 0;
+
+    loop {
+        match trainer.train_epoch::<true, true, _, _, _>(
+            &dataset,
+            &validation,
+            SUBDATASET_SIZE,
+            |i, trainer| {
+                println!("{} / {}", i * SUBDATASET_SIZE, dataset_len);
+                // params no sample of the chunk moved, ie: weights of always black pixels or saturated neurons
+                if let Some(report) = trainer.get_last_report() {
+                    println!(
+                        "gradient norm {} - dead params {} / {}",
+                        report.gradient_norm,
+                        report.zero_gradients,
+                        trainer.get_model_params().len()
+                    );
+                }
+            },
+        ) {
+            // only a better model overwrites the saved one
+            Ok(EpochOutcome::Improved { .. }) => {
+                failed_epochs = 0;
+                trainer.save("model.bin").unwrap();
+            }
+            Ok(EpochOutcome::Stale { .. }) => failed_epochs = 0,
+            Ok(EpochOutcome::Stopped { best_cost }) => {
+                println!("no improvement left, best validation cost {best_cost:?}");
+                break;
+            }
+            Err(err) => {
+                eprintln!("epoch aborted: {err}");
+                failed_epochs += 1;
+                if failed_epochs > trainer.get_early_stopping().patience {
+                    break;
+                }
+            }
+        }
 
-    while trainer.train_stocastic_step::<true, _>(&dataset, SUBDATASET_SIZE, |i, trainer| {
-        println!("{} / {}", i * SUBDATASET_SIZE, dataset.len());
-        trainer.save("model.bin").unwrap();
-        // trainer.shake(0.001);
-        // println!("{:?}", trainer.get_last_cost());
-    }) {
-        // println!("{:?}", trainer.get_model_params());
-        // println!("{:?}", trainer.get_last_cost());
         dataset.shuffle(&mut rng);
     }
+
+    let test_dataset: Vec<DataPoint<_, 10>> = load_data("mnist/t10k").unwrap();
+    let report = classification_report::<true, _, _, _, _, _>(
+        |input| trainer.eval(input),
+        &test_dataset,
+        3,
+    )
+    .unwrap();
+
+    println!(
+        "test accuracy: {} - top 3 accuracy: {}",
+        report.accuracy(),
+        report.top_k_accuracy()
+    );
+    for (digit, metrics) in report.classes().iter().enumerate() {
+        println!("{digit}: {metrics:?}");
+    }
 }
@@ -121,7 +121,7 @@ where
         for i in 0..m {
             for j in 0..p {
                 for k in 0..n {
-                    ret.0[i][j].accumulate(&(self.0[i][k].clone() * rhs.0[k][j].clone()))
+                    ret.0[i][j].accumulate_product(&self.0[i][k], &rhs.0[k][j])
                 }
             }
         }
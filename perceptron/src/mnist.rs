@@ -39,9 +39,7 @@ impl MnistData {
     }
 }
 
-pub fn load_data<const P: usize>(
-    dataset_name: &str,
-) -> Result<Vec<DataPoint<P, { 14 * 14 }, 10>>, std::io::Error> {
+pub fn load_data(dataset_name: &str) -> Result<Vec<DataPoint<{ 14 * 14 }, 10>>, std::io::Error> {
     let label_data = MnistData::new((File::open(format!("{}-labels-idx1-ubyte", dataset_name)))?)?;
     let images_data = MnistData::new((File::open(format!("{}-images-idx3-ubyte", dataset_name)))?)?;
     let mut images = Vec::new();
@@ -79,10 +77,7 @@ pub fn load_data<const P: usize>(
         let mut out = [0.; 10];
         out[classification as usize] = 1.;
 
-        ret.push(DataPoint {
-            input: image,
-            output: out,
-        })
+        ret.push(DataPoint::new(image, out))
     }
 
     Ok(ret)
@@ -95,13 +90,13 @@ mod mnist_tests {
 
     #[test]
     fn load_t10k() {
-        let dataset = load_data::<0>("mnist/t10k").unwrap();
+        let dataset = load_data("mnist/t10k").unwrap();
         assert_eq!(dataset.len(), 10_000);
     }
 
     #[test]
     fn load_train() {
-        let dataset = load_data::<0>("mnist/train").unwrap();
+        let dataset = load_data("mnist/train").unwrap();
         assert_eq!(dataset.len(), 60_000);
     }
 }
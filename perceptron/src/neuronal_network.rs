@@ -11,10 +11,33 @@ fn sigmoid<N: ExtendedArithmetic + Debug>(x: &mut N) {
     x.sigmoid_on_mut();
 }
 
+// checkpoints of one topology can't be loaded into another
+pub fn model_id(structure: &[usize]) -> String {
+    format!("mnist perceptron {structure:?}")
+}
+
+pub fn parameter_count(structure: &[usize]) -> usize {
+    structure
+        .windows(2)
+        .map(|layers| (layers[0] + 1) * layers[1])
+        .sum()
+}
+
+// false at the biases, in the same layout as the params
+pub fn regularization_mask(structure: &[usize]) -> Vec<bool> {
+    structure
+        .windows(2)
+        .flat_map(|layers| {
+            let (inputs, outputs) = (layers[0], layers[1]);
+            // a row per neuron, its weights followed by its bias
+            (0..outputs).flat_map(move |_| (0..=inputs).map(move |x| x != inputs))
+        })
+        .collect()
+}
+
 pub fn neuronal_network<
     const I: usize,
     const O: usize,
-    const P: usize,
     N: Clone
         + Debug
         + From<f32>
@@ -26,20 +49,14 @@ pub fn neuronal_network<
         + Div<N, Output = N>
         + ExtendedArithmetic,
 >(
-    params: &[N; P],
+    params: &[N],
     input: &[f32; I],
     structure: &Vec<usize>,
 ) -> [N; O] {
     {
         assert_eq!(structure[0], I);
         assert_eq!(structure[structure.len() - 1], O);
-
-        let mut spected_parameter_count = 0;
-
-        for i in 0..(structure.len() - 1) {
-            spected_parameter_count += (structure[i] + 1) * structure[i + 1]
-        }
-        assert_eq!(spected_parameter_count, P);
+        assert_eq!(parameter_count(structure), params.len());
     }
 
     let mut propagation = Matrix::deserialize(1, structure[0], &input.map(N::from));
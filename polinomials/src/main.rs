@@ -4,13 +4,16 @@ mod polinomial;
 mod piston_backend;
 
 use full_palette::GREEN_A700;
-use ia_engine::trainer::{default_param_translator, CriticalityCue, DataPoint, Trainer};
+use ia_engine::dual::Dual;
+use ia_engine::simd_arr::hybrid_simd::HybridSimd;
+use ia_engine::trainer::dataset::Generated;
+use ia_engine::trainer::{default_param_translator, DataPoint, StepOutcome, Trainer};
 use piston_backend::draw_piston_window;
 use piston_window::{PistonWindow, WindowSettings};
 use plotters::prelude::*;
 use crate::polinomial::polinomial;
 
-fn base_func(x: f32) -> f32 {
+fn base_func(x: f64) -> f64 {
     1. * (x * x * x * x * x) - 4. * (x * x * x * x) - 10. * (x * x * x)
         + 40. * (x * x)
         + 9. * x
@@ -26,16 +29,19 @@ fn main() {
         .build()
         .unwrap();
 
-    let mut trainer = Trainer::new_hybrid(CriticalityCue::<6>(), polinomial::<6,_>, polinomial::<6,_>, default_param_translator,());
-    // let mut trainer = Trainer::new_dense(polinomial::<6,_>, polinomial::<6,_>, default_param_translator,());
+    // the x⁵ terms run out of f32 precision, the model is trained in f64
+    let mut trainer: Trainer<_, _, _, _, Dual<6, HybridSimd<6, 6, f64>>, _, _, _, _> = Trainer::new(polinomial::<6,_,_>, polinomial::<6,_,_>, default_param_translator,());
+    // let mut trainer = Trainer::new_hybrid(CriticalityCue::<6>(), polinomial::<6,_,_>, polinomial::<6,_,_>, default_param_translator,());
 
     let mut epoch = 10;
 
     while let Some(_) = draw_piston_window(&mut window, |b|  {
         for _ in 0..1000 {
             let dataset = dataset_service(epoch);
-            let done = trainer.train_step_asintotic_search::<true,_,_>(&dataset, &dataset, dataset.len(), dataset.len() );
-            if !done {
+            let report = trainer
+                .train_step::<true, false, _, _>(&dataset, &dataset)
+                .unwrap();
+            if report.outcome == StepOutcome::Stalled {
                 epoch += 1;
                 break;
             }
@@ -59,7 +65,7 @@ fn main() {
             .draw_series(LineSeries::new(
                 (-100..=100)
                     .map(|x| x as f32 / 20.0)
-                    .map(|x| (x, base_func(x))),
+                    .map(|x| (x, base_func(x as f64) as f32)),
                 &RED,
             ))?
             .label("y = x⁵ -4x⁴ -10x³ +40x² +9x -11")
@@ -69,7 +75,7 @@ fn main() {
             .draw_series(LineSeries::new(
                 (-100..=100)
                     .map(|x| x as f32 / 20.0)
-                    .map(|x| (x, polinomial(&params, &[x], &())[0])),
+                    .map(|x| (x, polinomial(&params, &[x as f64], &())[0] as f32)),
                 &BLUE,
             ))?
             // .label(format!(
@@ -90,7 +96,7 @@ fn main() {
         chart.draw_series(LineSeries::new(
             (-abs_max..abs_max)
                 .map(|x| x as f32 / SPEED as f32)
-                .map(|x| (x, base_func(x))),
+                .map(|x| (x, base_func(x as f64) as f32)),
             &GREEN_A700,
         ))?;
 
@@ -108,15 +114,11 @@ fn main() {
     }
 }
 
-fn dataset_service<const P: usize>(epoch: isize) -> Vec<DataPoint<P, 1, 1>> {
+// the samples are built as the trainer asks for them, the dataset grows with the epoch for free
+fn dataset_service(epoch: isize) -> Generated<impl Fn(usize) -> DataPoint<1, 1, f64> + Sync> {
     let abs_max = epoch;
-    (-abs_max..abs_max)
-        .map(|x| x as f32 / SPEED as f32)
-        // .map(|x| x as f32 / 10.)
-        .map(|x| DataPoint {
-            input: [x],
-            output: [base_func(x)],
-        })
-        .collect::<Vec<_>>()
-
-    }
+    Generated::new(2 * abs_max as usize, move |i| {
+        let x = (i as isize - abs_max) as f64 / SPEED as f64;
+        DataPoint::new([x], [base_func(x)])
+    })
+}
@@ -3,17 +3,18 @@ use std::ops::{Add, Mul};
 
 pub fn polinomial<
     const G: usize,
+    T: Copy,
     N: Clone
         + Debug
         + From<f32>
-        + PartialOrd<f32>
+        + PartialOrd<T>
         + PartialOrd<N>
         + Add<N, Output = N>
         + Mul<N, Output = N>
-        + Mul<f32, Output = N>,
+        + Mul<T, Output = N>,
 >(
     params: &[N; G],
-    input: &[f32; 1],
+    input: &[T; 1],
     _: &(),
 ) -> [N; 1] {
     let mut ret = N::from(0.);